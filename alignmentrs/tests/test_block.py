@@ -0,0 +1,660 @@
+""" Unit tests for the Block class. """
+
+from nose.tools import *
+
+from libalignmentrs.position import Block, arrays_to_blocks
+
+
+class TestBlockMidpoint:
+    """ Unit tests for Block midpoint and center_int. """
+
+    def test_even_length(self):
+        """ Tests the midpoint of an even-length block. """
+        block = Block('s', 10, 20)
+        assert block.midpoint() == 15.0, \
+            'Expected midpoint 15.0, instead got {}'.format(block.midpoint())
+        assert block.center_int() == 15, \
+            'Expected center 15, instead got {}'.format(block.center_int())
+
+    def test_odd_length(self):
+        """ Tests the midpoint of an odd-length block. """
+        block = Block('s', 10, 15)
+        assert block.midpoint() == 12.5, \
+            'Expected midpoint 12.5, instead got {}'.format(block.midpoint())
+        assert block.center_int() == 12, \
+            'Expected center 12, instead got {}'.format(block.center_int())
+
+    def test_zero_length(self):
+        """ Tests if a zero-length block returns its start. """
+        block = Block('s', 7, 7)
+        assert block.midpoint() == 7.0, \
+            'Expected midpoint 7.0, instead got {}'.format(block.midpoint())
+        assert block.center_int() == 7, \
+            'Expected center 7, instead got {}'.format(block.center_int())
+
+    def test_large_coordinates(self):
+        """ Tests if coordinates near the integer limit do not overflow. """
+        block = Block('s', 2 ** 31 - 3, 2 ** 31 - 1)
+        assert block.midpoint() == 2 ** 31 - 2.0, \
+            'Expected midpoint {}, instead got {}'.format(
+                2 ** 31 - 2.0, block.midpoint())
+        assert block.center_int() == 2 ** 31 - 2, \
+            'Expected center {}, instead got {}'.format(
+                2 ** 31 - 2, block.center_int())
+
+
+class TestBlockApproxEq:
+    """ Unit tests for comparing blocks within a tolerance. """
+
+    def test_within_tolerance(self):
+        """ Tests if blocks within tolerance are equal. """
+        a = Block('s', 10, 20)
+        b = Block('s', 12, 19)
+        assert a.approx_eq(b, 2), 'Expected blocks to be equal'
+
+    def test_outside_tolerance(self):
+        """ Tests if blocks outside tolerance are not equal. """
+        a = Block('s', 10, 20)
+        b = Block('s', 13, 20)
+        assert not a.approx_eq(b, 2), 'Expected blocks not to be equal'
+
+    def test_mismatched_ids(self):
+        """ Tests if blocks with different ids are never equal. """
+        a = Block('s', 10, 20)
+        b = Block('g', 10, 20)
+        assert not a.approx_eq(b, 100), 'Expected blocks not to be equal'
+
+
+class TestBlockSortByLength:
+    """ Unit tests for comparing and sorting blocks by length. """
+    def setup(self):
+        self.blocks = [
+            Block('a', 0, 5), Block('b', 10, 12), Block('c', 20, 25),
+            Block('d', 30, 40),
+        ]
+
+    def teardown(self):
+        pass
+
+    def test_longer_than(self):
+        """ Tests if longer_than compares block lengths. """
+        assert self.blocks[0].longer_than(self.blocks[1]), \
+            'Expected {} to be longer'.format(self.blocks[0])
+        assert not self.blocks[0].longer_than(self.blocks[2]), \
+            'Expected {} not to be longer'.format(self.blocks[0])
+
+    def test_ascending(self):
+        """ Tests sorting by length in ascending order with a tie. """
+        exp = ['b', 'a', 'c', 'd']
+        test = [b.id for b in Block.sort_by_length(self.blocks, False)]
+        assert exp == test, \
+            'Expected order {}, instead got {}'.format(exp, test)
+
+    def test_descending(self):
+        """ Tests sorting by length in descending order with a tie. """
+        exp = ['d', 'a', 'c', 'b']
+        test = [b.id for b in Block.sort_by_length(self.blocks, True)]
+        assert exp == test, \
+            'Expected order {}, instead got {}'.format(exp, test)
+
+
+class TestBlockDistanceTo:
+    """ Unit tests for measuring the distance between blocks. """
+    def setup(self):
+        self.block = Block('a', 0, 5)
+
+    def teardown(self):
+        pass
+
+    def test_overlapping(self):
+        """ Tests if overlapping blocks have a distance of 0. """
+        other = Block('b', 3, 8)
+        assert self.block.distance_to(other) == 0, \
+            'Expected distance 0, instead got {}'.format(
+                self.block.distance_to(other))
+        assert other.distance_to(self.block) == 0, \
+            'Expected distance 0, instead got {}'.format(
+                other.distance_to(self.block))
+
+    def test_abutting(self):
+        """ Tests if abutting blocks have a distance of 0. """
+        other = Block('b', 5, 9)
+        assert self.block.distance_to(other) == 0, \
+            'Expected distance 0, instead got {}'.format(
+                self.block.distance_to(other))
+        assert other.distance_to(self.block) == 0, \
+            'Expected distance 0, instead got {}'.format(
+                other.distance_to(self.block))
+
+    def test_separated(self):
+        """ Tests the distance between separated blocks. """
+        other = Block('b', 10, 15)
+        assert self.block.distance_to(other) == 5, \
+            'Expected distance 5, instead got {}'.format(
+                self.block.distance_to(other))
+        assert other.distance_to(self.block) == 5, \
+            'Expected distance 5, instead got {}'.format(
+                other.distance_to(self.block))
+
+    def test_gap_of_three(self):
+        """ Tests if [0, 5) and [8, 12) are 3 positions apart. """
+        other = Block('b', 8, 12)
+        exp = 3
+        test = self.block.distance_to(other)
+        assert exp == test, 'Expected distance {}, instead got {}'.format(exp, test)
+
+
+class TestBlockStrict:
+    """ Unit tests for rejecting zero-length blocks in strict mode. """
+
+    def test_not_strict(self):
+        """ Tests if a zero-length block is allowed by default. """
+        block = Block('s', 5, 5)
+        assert (block.start, block.stop) == (5, 5), \
+            'Expected (5, 5), instead got {}'.format((block.start, block.stop))
+
+    @raises(ValueError)
+    def test_strict(self):
+        """ Tests if a zero-length block raises ValueError in strict mode. """
+        Block('s', 5, 5, strict=True)
+
+    def test_strict_nonzero(self):
+        """ Tests if a non-empty block is allowed in strict mode. """
+        block = Block('s', 5, 6, strict=True)
+        assert (block.start, block.stop) == (5, 6), \
+            'Expected (5, 6), instead got {}'.format((block.start, block.stop))
+
+
+class TestBlockUnion:
+    """ Unit tests for the spanning block of two blocks. """
+    def setup(self):
+        self.block = Block('a', 0, 5)
+
+    def teardown(self):
+        pass
+
+    def test_overlapping(self):
+        """ Tests the union of overlapping blocks. """
+        test = self.block.union(Block('b', 3, 8))
+        exp = ('a', 0, 8)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    def test_abutting(self):
+        """ Tests the union of abutting blocks. """
+        test = self.block.union(Block('b', 5, 9))
+        exp = ('a', 0, 9)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    def test_disjoint(self):
+        """ Tests if disjoint blocks produce a single spanning block. """
+        test = Block('b', 10, 15).union(self.block)
+        exp = ('b', 0, 15)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestBlockFromSlice:
+    """ Unit tests for creating a block from a slice. """
+
+    def test_full_slice(self):
+        """ Tests if a full slice covers the whole length. """
+        test = Block.from_slice('s', slice(None, None), 10)
+        exp = ('s', 0, 10)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    def test_negative_index(self):
+        """ Tests if negative indices are resolved against the length. """
+        test = Block.from_slice('s', slice(-4, -1), 10)
+        exp = ('s', 6, 9)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_stepped_slice(self):
+        """ Tests if a slice with a step raises ValueError. """
+        Block.from_slice('s', slice(0, 10, 2), 10)
+
+
+class TestBlockScale:
+    """ Unit tests for rescaling block coordinates. """
+
+    def test_codon_to_nucleotide(self):
+        """ Tests scaling by 3 and that the length scales accordingly. """
+        block = Block('c', 2, 5)
+        test = block.scale(3)
+        exp = ('c', 6, 15)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+        assert test.stop - test.start == 3 * (block.stop - block.start), \
+            'Expected length {}, instead got {}'.format(
+                3 * (block.stop - block.start), test.stop - test.start)
+
+    @raises(ValueError)
+    def test_invalid_factor(self):
+        """ Tests if a non-positive factor raises ValueError. """
+        Block('c', 2, 5).scale(0)
+
+    @raises(OverflowError)
+    def test_overflow(self):
+        """ Tests if coordinates too large to scale raise OverflowError. """
+        Block('s', 0, 2 ** 30).scale(3)
+
+
+class TestBlockContains:
+    """ Unit tests for the in operator on Block. """
+    def setup(self):
+        self.block = Block('s', 10, 20)
+
+    def teardown(self):
+        pass
+
+    def test_contained(self):
+        """ Tests if a position inside the block is contained. """
+        assert 15 in self.block, 'Expected 15 to be in the block'
+
+    def test_start(self):
+        """ Tests if the start position is contained. """
+        assert 10 in self.block, 'Expected 10 to be in the block'
+
+    def test_stop(self):
+        """ Tests if the stop position is not contained. """
+        assert 20 not in self.block, 'Expected 20 not to be in the block'
+
+    def test_below_start(self):
+        """ Tests if a position below the start is not contained. """
+        assert 9 not in self.block, 'Expected 9 not to be in the block'
+
+
+class TestBlockBytes:
+    """ Unit tests for encoding a block as a binary record. """
+
+    def test_roundtrip(self):
+        """ Tests if a block with a multibyte id survives a round trip. """
+        block = Block('exón→', 3, 70000)
+        data = block.to_bytes()
+        assert isinstance(data, bytes), \
+            'Expected bytes, instead got {}'.format(type(data))
+        test = Block.from_bytes(data)
+        exp = (block.id, block.start, block.stop)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_truncated(self):
+        """ Tests if a truncated record raises ValueError. """
+        Block.from_bytes(Block('s', 0, 1).to_bytes()[:-1])
+
+
+class TestBlockWithId:
+    """ Unit tests for relabeling a copy of a block. """
+
+    def test_with_id(self):
+        """ Tests if the copy has the new id and the original is kept. """
+        block = Block('s', 3, 8)
+        test = block.with_id('exon')
+        exp = ('exon', 3, 8)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+        assert block.id == 's', \
+            'Expected original id s, instead got {}'.format(block.id)
+
+
+class TestArraysToBlocksDuplicates:
+    """ Unit tests for repeated coordinates in arrays_to_blocks. """
+    def setup(self):
+        self.data = [3, 4, 4, 5]
+        self.ids = ['s', 's', 's', 's']
+
+    def teardown(self):
+        pass
+
+    def test_lenient(self):
+        """ Tests if a repeated coordinate is merged by default. """
+        exp = [('s', 3, 6)]
+        test = [(b.id, b.start, b.stop)
+                for b in arrays_to_blocks(self.data, self.ids)]
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_strict(self):
+        """ Tests if a repeated coordinate raises ValueError when strict. """
+        arrays_to_blocks(self.data, self.ids, strict=True)
+
+
+class TestBlockTuple:
+    """ Unit tests for converting blocks to and from tuples. """
+
+    def test_roundtrip(self):
+        """ Tests if a block survives a round trip through a tuple. """
+        block = Block('exon', 3, 8)
+        exp = ('exon', 3, 8)
+        assert exp == block.to_tuple(), \
+            'Expected {}, instead got {}'.format(exp, block.to_tuple())
+        test = Block.from_tuple(block.to_tuple())
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid(self):
+        """ Tests if start greater than stop raises ValueError. """
+        Block.from_tuple(('exon', 8, 3))
+
+
+class TestBlockClipToNonnegative:
+    """ Unit tests for clamping blocks to non-negative coordinates. """
+
+    def test_negative(self):
+        """ Tests if a fully negative block returns None. """
+        test = Block('s', -10, -2).clip_to_nonnegative()
+        assert test is None, 'Expected None, instead got {}'.format(test)
+
+    def test_straddling(self):
+        """ Tests if a straddling block is clipped at 0. """
+        test = Block('s', -5, 5).clip_to_nonnegative()
+        exp = ('s', 0, 5)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    def test_positive(self):
+        """ Tests if a positive block is unchanged. """
+        test = Block('s', 3, 8).clip_to_nonnegative()
+        exp = ('s', 3, 8)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestBlockToGff3:
+    """ Unit tests for exporting a block as a GFF3 line. """
+
+    def test_coordinates(self):
+        """ Tests if [0, 5) is written as 1-based inclusive 1 5. """
+        test = Block('exon1', 0, 5).to_gff3('chr1', 'alignmentrs', 'exon')
+        exp = 'chr1\talignmentrs\texon\t1\t5\t.\t.\t.\tID=exon1'
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_empty_fields(self):
+        """ Tests if empty fields are written as dots. """
+        test = Block('exon1', 0, 5).to_gff3('chr1', '', '').split('\t')
+        exp = ['chr1', '.', '.']
+        assert exp == test[:3], 'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestBlockWindows:
+    """ Unit tests for iterating over sub-blocks of a block. """
+
+    def setup(self):
+        self.block = Block('exon', 10, 17)
+
+    def teardown(self):
+        pass
+
+    def test_tiles(self):
+        """ Tests non-overlapping windows with window 3 step 3. """
+        exp = [('exon', 10, 13), ('exon', 13, 16), ('exon', 16, 17)]
+        test = [(b.id, b.start, b.stop) for b in self.block.windows(3, 3)]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_tiles_drop_last(self):
+        """ Tests if drop_last skips the partial window. """
+        exp = [('exon', 10, 13), ('exon', 13, 16)]
+        test = [(b.id, b.start, b.stop)
+                for b in self.block.windows(3, 3, drop_last=True)]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_overlapping(self):
+        """ Tests overlapping windows with window 3 step 1. """
+        exp = [(10, 13), (11, 14), (12, 15), (13, 16), (14, 17)]
+        test = [(b.start, b.stop) for b in self.block.windows(3, 1)]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid(self):
+        """ Tests if a non-positive window raises ValueError. """
+        self.block.windows(0, 1)
+
+
+class TestBlockToCigarStr:
+    """ Unit tests for converting blocks to CIGAR operations. """
+
+    def test_match(self):
+        """ Tests if a match block gives a count and operation. """
+        exp = '10M'
+        test = Block('M', 10, 20).to_cigar_str()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_deletion(self):
+        """ Tests if a deletion block gives a D operation. """
+        exp = '10D'
+        test = Block('D', 10, 20).to_cigar_str()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_chained(self):
+        """ Tests chaining consecutive blocks using offset. """
+        blocks = [Block('M', 0, 5), Block('I', 5, 7), Block('M', 7, 10)]
+        offset = 0
+        parts = []
+        for block in blocks:
+            parts.append(block.to_cigar_str(offset))
+            offset = block.stop
+        exp = '5M2I3M'
+        test = ''.join(parts)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_zero_length(self):
+        """ Tests if a zero-length block gives an empty string. """
+        exp = ''
+        test = Block('M', 5, 5).to_cigar_str()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_offset_mismatch(self):
+        """ Tests if a block not starting at offset raises ValueError. """
+        Block('M', 10, 20).to_cigar_str(9)
+
+    @raises(ValueError)
+    def test_invalid_operation(self):
+        """ Tests if an invalid id raises ValueError. """
+        Block('s', 10, 20).to_cigar_str()
+
+
+class TestBlockFromCigarStr:
+    """ Unit tests for creating blocks from CIGAR operations. """
+
+    def test_match(self):
+        """ Tests if a match operation starts at the reference offset. """
+        test = Block.from_cigar_str('45M', 100)
+        exp = ('M', 100, 145)
+        assert exp == (test.id, test.start, test.stop), \
+            'Expected {}, instead got {}'.format(exp, test)
+
+    def test_roundtrip(self):
+        """ Tests if an operation survives a round trip. """
+        exp = '3D'
+        test = Block.from_cigar_str(exp, 10).to_cigar_str()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_order(self):
+        """ Tests if an operation before the count raises ValueError. """
+        Block.from_cigar_str('X45', 0)
+
+    @raises(ValueError)
+    def test_empty(self):
+        """ Tests if an empty string raises ValueError. """
+        Block.from_cigar_str('', 0)
+
+    @raises(ValueError)
+    def test_zero_length(self):
+        """ Tests if a length of 0 raises ValueError. """
+        Block.from_cigar_str('0M', 0)
+
+    @raises(OverflowError)
+    def test_overflow(self):
+        """ Tests if a block ending past the integer limit raises
+        OverflowError. """
+        Block.from_cigar_str('10M', 2 ** 31 - 5)
+
+
+class TestBlockCoordinates:
+    """ Unit tests for the coordinates property. """
+
+    def test_unpack(self):
+        """ Tests if coordinates unpacks into start and stop. """
+        block = Block('exon', 3, 8)
+        s, e = block.coordinates
+        exp = (block.start, block.stop)
+        assert exp == (s, e), 'Expected {}, instead got {}'.format(exp, (s, e))
+
+
+class TestBlockOverlap:
+    """ Unit tests for overlap between blocks. """
+
+    def test_adjacent(self):
+        """ Tests if abutting blocks do not overlap. """
+        a = Block('s', 0, 5)
+        b = Block('s', 5, 10)
+        assert not a.overlaps_with(b), 'Expected no overlap'
+        exp = 0
+        test = a.overlap_length(b)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_partial(self):
+        """ Tests partially overlapping blocks. """
+        a = Block('s', 0, 6)
+        b = Block('g', 4, 10)
+        assert a.overlaps_with(b), 'Expected an overlap'
+        exp = 2
+        test = a.overlap_length(b)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_contained(self):
+        """ Tests a block contained in another. """
+        a = Block('s', 0, 10)
+        b = Block('s', 3, 5)
+        exp = 2
+        test = a.overlap_length(b)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_disjoint(self):
+        """ Tests separated blocks. """
+        a = Block('s', 0, 3)
+        b = Block('s', 7, 9)
+        assert not b.overlaps_with(a), 'Expected no overlap'
+
+
+class TestBlockEquality:
+    """ Unit tests for comparing blocks for equality. """
+
+    def test_equal(self):
+        """ Tests if blocks with the same id and coordinates are equal. """
+        a = Block('exon', 0, 5)
+        b = Block('exon', 0, 5)
+        assert a == b, 'Expected {} == {}'.format(a, b)
+        assert not a != b, 'Expected not {} != {}'.format(a, b)
+
+    def test_different_id(self):
+        """ Tests if blocks differing only in id are not equal. """
+        a = Block('exon', 0, 5)
+        b = Block('intron', 0, 5)
+        assert a != b, 'Expected {} != {}'.format(a, b)
+
+    def test_different_coordinates(self):
+        """ Tests if blocks differing only in coordinates are not equal. """
+        a = Block('exon', 0, 5)
+        b = Block('exon', 0, 6)
+        assert a != b, 'Expected {} != {}'.format(a, b)
+        assert not a == b, 'Expected not {} == {}'.format(a, b)
+
+    @raises(TypeError)
+    def test_unhashable(self):
+        """ Tests if hashing a block raises TypeError. """
+        {Block('exon', 0, 5), Block('exon', 0, 5)}
+
+
+class TestBlockOrdering:
+    """ Unit tests for ordering blocks by start, stop, and id. """
+
+    def test_sorted(self):
+        """ Tests if a mixed list of blocks sorts correctly. """
+        blocks = [Block('s', 5, 9), Block('g', 0, 3), Block('s', 0, 3),
+                  Block('a', 5, 7), Block('s', -1, 2)]
+        exp = [('s', -1, 2), ('g', 0, 3), ('s', 0, 3), ('a', 5, 7),
+               ('s', 5, 9)]
+        test = [(b.id, b.start, b.stop) for b in sorted(blocks)]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_comparisons(self):
+        """ Tests the rich comparison operators. """
+        a = Block('s', 0, 3)
+        b = Block('s', 0, 5)
+        assert a < b, 'Expected {} < {}'.format(a, b)
+        assert a <= b, 'Expected {} <= {}'.format(a, b)
+        assert b > a, 'Expected {} > {}'.format(b, a)
+        assert b >= a, 'Expected {} >= {}'.format(b, a)
+
+    def test_id_breaks_ties(self):
+        """ Tests if the id is compared when coordinates are equal. """
+        a = Block('a', 0, 3)
+        b = Block('b', 0, 3)
+        assert a < b, 'Expected {} < {}'.format(a, b)
+
+
+class TestBlockSplitAt:
+    """ Unit tests for splitting a block at an absolute position. """
+
+    def setup(self):
+        self.block = Block('M', 10, 20)
+
+    def teardown(self):
+        pass
+
+    def test_split(self):
+        """ Tests if the block is split into two at the position. """
+        left, right = self.block.split_at(15)
+        exp = (('M', 10, 15), ('M', 15, 20))
+        test = ((left.id, left.start, left.stop),
+                (right.id, right.start, right.stop))
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_at_start(self):
+        """ Tests if splitting at start raises ValueError. """
+        self.block.split_at(10)
+
+    @raises(ValueError)
+    def test_at_stop(self):
+        """ Tests if splitting at stop raises ValueError. """
+        self.block.split_at(20)
+
+
+class TestBlockContainsBlock:
+    """ Unit tests for checking if a block contains another. """
+
+    def setup(self):
+        self.block = Block('s', 0, 10)
+
+    def teardown(self):
+        pass
+
+    def test_equal(self):
+        """ Tests if a block contains an equal block. """
+        assert self.block.contains_block(Block('s', 0, 10)), \
+            'Expected {} to contain itself'.format(self.block)
+
+    def test_single_position(self):
+        """ Tests single-position blocks inside and outside the block. """
+        assert self.block.contains_block(Block('s', 9, 10)), \
+            'Expected [9, 10) to be contained'
+        assert not self.block.contains_block(Block('s', 10, 11)), \
+            'Expected [10, 11) not to be contained'
+
+    def test_overlapping(self):
+        """ Tests if an overlapping block is not contained. """
+        other = Block('s', 5, 15)
+        assert self.block.overlaps_with(other), 'Expected an overlap'
+        assert not self.block.contains_block(other), \
+            'Expected {} not to be contained'.format(other)
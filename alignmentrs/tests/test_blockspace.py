@@ -0,0 +1,683 @@
+""" Unit tests for the BlockSpace class. """
+
+from nose.tools import *
+
+from libalignmentrs.position import Block, BlockSpace, list_to_linspace
+from libalignmentrs.position import compressed_str_v2_to_linspace
+
+
+class TestBlockSpaceRepair:
+    """ Unit tests for repairing an unsorted, overlapping BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([
+            ('s', 10, 20), ('s', 0, 5), ('s', 15, 25), ('s', 30, 30),
+        ])
+
+    def teardown(self):
+        pass
+
+    @raises(ValueError)
+    def test_validate_invalid(self):
+        """ Tests if validate raises ValueError before repair. """
+        self.space.validate()
+
+    def test_repair(self):
+        """ Tests if repair sorts, trims and drops blocks. """
+        corrections = self.space.repair()
+        exp = [('s', 0, 5), ('s', 10, 20), ('s', 20, 25)]
+        test = self.space.to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+        assert corrections == 4, \
+            'Expected 4 corrections, instead got {}'.format(corrections)
+        self.space.validate()
+
+
+class TestBlockSpaceContains:
+    """ Unit tests for the in operator on BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([('s', 0, 5), ('s', 5, 10), ('s', 20, 30)])
+
+    def teardown(self):
+        pass
+
+    def test_int(self):
+        """ Tests if a covered position is in the space. """
+        assert 25 in self.space, 'Expected 25 to be in the space'
+        assert 15 not in self.space, 'Expected 15 not to be in the space'
+
+    def test_contained_block(self):
+        """ Tests if a block spanning adjacent blocks is in the space. """
+        block = Block('s', 3, 8)
+        assert block in self.space, \
+            'Expected {} to be in the space'.format(block)
+
+    def test_partial_block(self):
+        """ Tests if a partially covered block is not in the space. """
+        block = Block('s', 8, 21)
+        assert block not in self.space, \
+            'Expected {} not to be in the space'.format(block)
+
+    @raises(TypeError)
+    def test_unsupported_type(self):
+        """ Tests if an unsupported type raises TypeError. """
+        'a' in self.space
+
+
+class TestBlockSpaceCompressedStrV2:
+    """ Unit tests for the lower bound-prefixed compressed string. """
+    def setup(self):
+        self.blocks = [('s', 10, 15), ('g', 15, 18), ('s', 18, 20)]
+        self.space = list_to_linspace(self.blocks)
+
+    def teardown(self):
+        pass
+
+    def test_to_str(self):
+        """ Tests if the lower bound is prefixed to the string. """
+        exp = '10|s=5;g=3;s=2'
+        test = self.space.to_compressed_str_v2()
+        assert exp == test, \
+            'Expected string {}, instead got {}'.format(exp, test)
+
+    def test_roundtrip(self):
+        """ Tests if parsing the string recovers the exact coordinates. """
+        test = compressed_str_v2_to_linspace(
+            self.space.to_compressed_str_v2()).to_list()
+        assert self.blocks == test, \
+            'Expected blocks {}, instead got {}'.format(self.blocks, test)
+
+    @raises(ValueError)
+    def test_non_contiguous(self):
+        """ Tests if non-contiguous blocks raise ValueError. """
+        list_to_linspace([('s', 0, 5), ('s', 6, 8)]).to_compressed_str_v2()
+
+    @raises(ValueError)
+    def test_reserved_char_in_id(self):
+        """ Tests if an id containing a separator raises ValueError. """
+        list_to_linspace([('s;g', 0, 5)]).to_compressed_str_v2()
+
+
+class TestBlockSpaceExtractRanges:
+    """ Unit tests for extracting relative ranges from a BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([('a', 0, 5), ('b', 10, 15), ('a', 20, 25)])
+
+    def teardown(self):
+        pass
+
+    def test_spanning_range(self):
+        """ Tests extracting a range that spans several blocks. """
+        exp = [('a', 3, 5), ('b', 10, 15), ('a', 20, 22)]
+        test = self.space.extract_ranges([(3, 12)]).to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_subdividing_ranges(self):
+        """ Tests extracting ranges that subdivide blocks. """
+        exp = [('b', 11, 13), ('a', 0, 3)]
+        test = self.space.extract_ranges([(6, 8), (0, 2), (2, 3)]).to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_contiguous_blocks_kept(self):
+        """ Tests if separate contiguous blocks within a range are not
+        merged. """
+        space = list_to_linspace([('s', 0, 5), ('s', 5, 10)])
+        exp = [('s', 2, 5), ('s', 5, 8)]
+        test = space.extract_ranges([(2, 8)]).to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if a range past the end raises IndexError. """
+        self.space.extract_ranges([(0, 16)])
+
+    @raises(ValueError)
+    def test_overlapping_ranges(self):
+        """ Tests if overlapping ranges raise ValueError. """
+        self.space.extract_ranges([(0, 6), (5, 7)])
+
+
+class TestBlockSpaceSplitById:
+    """ Unit tests for splitting a BlockSpace by block id. """
+    def setup(self):
+        self.space = list_to_linspace([
+            ('exon', 0, 5), ('intron', 5, 20), ('exon', 20, 30),
+            ('intron', 30, 42),
+        ])
+
+    def teardown(self):
+        pass
+
+    def test_split_by_id(self):
+        """ Tests if each sub-space contains only the blocks of its id. """
+        test = {k: v.to_list() for k, v in self.space.split_by_id().items()}
+        exp = {
+            'exon': [('exon', 0, 5), ('exon', 20, 30)],
+            'intron': [('intron', 5, 20), ('intron', 30, 42)],
+        }
+        assert exp == test, \
+            'Expected sub-spaces {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceToDot:
+    """ Unit tests for exporting a BlockSpace as a DOT graph. """
+    def setup(self):
+        self.space = list_to_linspace([('s', 0, 5), ('g', 0, 2), ('s', 5, 9)])
+
+    def teardown(self):
+        pass
+
+    def test_nodes_and_edges(self):
+        """ Tests if there is one node per block and one edge between
+        consecutive blocks. """
+        test = self.space.to_dot()
+        assert test.count('[label=') == 3, \
+            'Expected 3 nodes, instead got {}'.format(test)
+        assert test.count('->') == 2, \
+            'Expected 2 edges, instead got {}'.format(test)
+        assert '"g:0-2"' in test, \
+            'Expected gap node label, instead got {}'.format(test)
+
+    def test_escaped_label(self):
+        """ Tests if quotes and backslashes in ids are escaped. """
+        space = list_to_linspace([('a"b\\c', 0, 2)])
+        test = space.to_dot()
+        exp = '[label="a\\"b\\\\c:0-2"'
+        assert exp in test, \
+            'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceGaps:
+    """ Unit tests for listing the uncovered regions of a BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([
+            ('s', 0, 5), ('s', 10, 20), ('s', 15, 18), ('s', 25, 30),
+        ])
+
+    def teardown(self):
+        pass
+
+    def test_gaps_between(self):
+        """ Tests if uncovered regions are returned as gap blocks. """
+        exp = [('g', 5, 10), ('g', 20, 25)]
+        test = [(b.id, b.start, b.stop) for b in self.space.gaps_between()]
+        assert exp == test, \
+            'Expected gaps {}, instead got {}'.format(exp, test)
+
+    def test_iter_gaps(self):
+        """ Tests if the iterator yields the same blocks as gaps_between
+        and is exhaustible. """
+        exp = [(b.id, b.start, b.stop) for b in self.space.gaps_between()]
+        gaps = self.space.iter_gaps()
+        test = [(b.id, b.start, b.stop) for b in gaps]
+        assert exp == test, \
+            'Expected gaps {}, instead got {}'.format(exp, test)
+        assert list(gaps) == [], 'Expected the iterator to be exhausted'
+
+
+class TestBlockSpaceFromExtendedStr:
+    """ Unit tests for parsing the extended string representation. """
+    def setup(self):
+        self.blocks = [('exon', 0, 5), ('intron', 5, 20), ('exon', 25, 30)]
+        self.space = list_to_linspace(self.blocks)
+
+    def teardown(self):
+        pass
+
+    def test_roundtrip(self):
+        """ Tests if parsing the string recovers the original blocks. """
+        test = BlockSpace.from_extended_str(self.space.to_block_str()).to_list()
+        assert self.blocks == test, \
+            'Expected blocks {}, instead got {}'.format(self.blocks, test)
+
+    @raises(ValueError)
+    def test_malformed_segment(self):
+        """ Tests if a malformed segment raises ValueError. """
+        BlockSpace.from_extended_str('exon=0:5;intron=5')
+
+    @raises(ValueError)
+    def test_overlapping(self):
+        """ Tests if overlapping blocks raise ValueError. """
+        BlockSpace.from_extended_str('exon=0:5;intron=3:8')
+
+
+class TestBlockSpaceOverlapCounts:
+    """ Unit tests for counting covered positions within query blocks. """
+    def setup(self):
+        self.space = list_to_linspace([('s', 0, 5), ('s', 10, 20), ('s', 30, 40)])
+
+    def teardown(self):
+        pass
+
+    def test_overlap_counts(self):
+        """ Tests queries overlapping zero, one and multiple blocks. """
+        queries = [Block('q', 5, 10), Block('q', 12, 15), Block('q', 3, 35)]
+        exp = [0, 3, 17]
+        test = self.space.overlap_counts(queries)
+        assert exp == test, \
+            'Expected counts {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceLabelRanges:
+    """ Unit tests for relabeling a BlockSpace by absolute ranges. """
+    def setup(self):
+        self.space = list_to_linspace([('s', 0, 10), ('s', 20, 30)])
+
+    def teardown(self):
+        pass
+
+    def test_split_block(self):
+        """ Tests labeling a range inside a block. """
+        self.space.label_ranges([(3, 6, 'exon')])
+        exp = [('s', 0, 3), ('exon', 3, 6), ('s', 6, 10), ('s', 20, 30)]
+        test = self.space.to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_overlapping_ranges(self):
+        """ Tests if later ranges win where ranges overlap. """
+        self.space.label_ranges([(5, 25, 'a'), (8, 22, 'b')])
+        exp = [('s', 0, 5), ('a', 5, 8), ('b', 8, 10), ('b', 20, 22),
+               ('a', 22, 25), ('s', 25, 30)]
+        test = self.space.to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_non_overlapping_range(self):
+        """ Tests if a range outside the space leaves blocks unchanged. """
+        space = list_to_linspace([('s', 0, 5), ('s', 5, 10), ('a', 10, 12)])
+        space.label_ranges([(20, 30, 'x')])
+        exp = [('s', 0, 5), ('s', 5, 10), ('a', 10, 12)]
+        test = space.to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_merge_within_range(self):
+        """ Tests if contiguous blocks relabeled by one range are merged. """
+        space = list_to_linspace([('s', 0, 5), ('a', 5, 10), ('s', 10, 12)])
+        space.label_ranges([(3, 8, 'x')])
+        exp = [('s', 0, 3), ('x', 3, 8), ('a', 8, 10), ('s', 10, 12)]
+        test = space.to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceCoveredLength:
+    """ Unit tests for the length of non-gap blocks. """
+    def setup(self):
+        self.space = list_to_linspace([
+            ('s', 0, 5), ('g', 0, 3), ('s', 5, 9), ('n', 0, 2),
+        ])
+
+    def teardown(self):
+        pass
+
+    def test_default_gap_id(self):
+        """ Tests if blocks with the default gap id are excluded. """
+        test = self.space.covered_length()
+        assert test == 11, 'Expected length 11, instead got {}'.format(test)
+
+    def test_custom_gap_id(self):
+        """ Tests if blocks with a custom gap id are excluded. """
+        test = self.space.covered_length('n')
+        assert test == 12, 'Expected length 12, instead got {}'.format(test)
+
+
+class TestBlockSpaceRemoveStable:
+    """ Unit tests for removing positions with attached payload. """
+    def setup(self):
+        self.space = list_to_linspace([('s', 0, 10)])
+
+    def teardown(self):
+        pass
+
+    def test_payload_order(self):
+        """ Tests if payload of equal positions keeps its order. """
+        test = self.space.remove_stable([5, 1, 5, 3], ['a', 'b', 'c', 'd'])
+        exp = ['b', 'd', 'a', 'c']
+        assert exp == test, \
+            'Expected payload {}, instead got {}'.format(exp, test)
+        exp = [('s', 0, 1), ('s', 2, 3), ('s', 4, 5), ('s', 6, 10)]
+        test = self.space.to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_length_mismatch(self):
+        """ Tests if mismatched lengths raise ValueError. """
+        self.space.remove_stable([1, 2], ['a'])
+
+
+class TestBlockSpaceBounds:
+    """ Unit tests for the bounds of a BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([
+            ('s', 0, 0), ('s', 2, 5), ('s', 7, 9), ('s', 12, 12),
+        ])
+
+    def teardown(self):
+        pass
+
+    def test_bounds(self):
+        """ Tests if bounds matches lb and ub. """
+        exp = (self.space.lb(), self.space.ub())
+        test = self.space.bounds()
+        assert exp == test, \
+            'Expected bounds {}, instead got {}'.format(exp, test)
+
+    def test_tighten(self):
+        """ Tests if tighten drops empty blocks at both ends. """
+        self.space.tighten()
+        exp = (2, 9)
+        test = self.space.bounds()
+        assert exp == test, \
+            'Expected bounds {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_empty(self):
+        """ Tests if an empty space raises ValueError. """
+        list_to_linspace([]).bounds()
+
+
+class TestBlockSpaceIntersectionLength:
+    """ Unit tests for counting covered positions within a block. """
+    def setup(self):
+        self.space = list_to_linspace([('s', 0, 5), ('s', 10, 20)])
+
+    def teardown(self):
+        pass
+
+    def test_two_blocks(self):
+        """ Tests a block overlapping two blocks of the space. """
+        test = self.space.intersection_length(Block('q', 3, 12))
+        assert test == 4, 'Expected 4, instead got {}'.format(test)
+
+    def test_outside(self):
+        """ Tests a block outside of the space. """
+        test = self.space.intersection_length(Block('q', 30, 40))
+        assert test == 0, 'Expected 0, instead got {}'.format(test)
+
+
+class TestBlockSpaceOverlay:
+    """ Unit tests for overlaying two BlockSpaces by id priority. """
+    def setup(self):
+        self.space = list_to_linspace([('cds', 0, 10), ('cds', 20, 30)])
+        self.other = list_to_linspace([('utr', 5, 25)])
+
+    def teardown(self):
+        pass
+
+    def test_other_wins(self):
+        """ Tests if the id of the other space wins by priority. """
+        exp = [('cds', 0, 5), ('utr', 5, 25), ('cds', 25, 30)]
+        test = self.space.overlay(self.other, ['utr', 'cds']).to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_self_wins(self):
+        """ Tests if the id of this space wins by priority. """
+        exp = [('cds', 0, 10), ('utr', 10, 20), ('cds', 20, 30)]
+        test = self.space.overlay(self.other, ['cds']).to_list()
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceBoundaryPoints:
+    """ Unit tests for the sweep-line boundary points of a BlockSpace. """
+
+    def test_two_blocks(self):
+        """ Tests the four boundary points of a two-block space. """
+        space = list_to_linspace([('s', 0, 5), ('s', 10, 20)])
+        exp = [(0, 1), (5, -1), (10, 1), (20, -1)]
+        test = space.boundary_points()
+        assert exp == test, \
+            'Expected points {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceCut:
+    """ Unit tests for cutting a relative range out of a BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([('a', 0, 5), ('b', 10, 15), ('a', 5, 9)])
+
+    def teardown(self):
+        pass
+
+    def test_across_blocks(self):
+        """ Tests cutting across block boundaries and merging the rest. """
+        exp = [('b', 10, 15)]
+        test = self.space.cut(5, 10).to_list()
+        assert exp == test, \
+            'Expected cut blocks {}, instead got {}'.format(exp, test)
+        exp = [('a', 0, 9)]
+        test = self.space.to_list()
+        assert exp == test, \
+            'Expected remaining blocks {}, instead got {}'.format(exp, test)
+
+    def test_within_block(self):
+        """ Tests cutting within a single block. """
+        exp = [('a', 1, 3)]
+        test = self.space.cut(1, 3).to_list()
+        assert exp == test, \
+            'Expected cut blocks {}, instead got {}'.format(exp, test)
+        exp = [('a', 0, 1), ('a', 3, 5), ('b', 10, 15), ('a', 5, 9)]
+        test = self.space.to_list()
+        assert exp == test, \
+            'Expected remaining blocks {}, instead got {}'.format(exp, test)
+
+    def test_untouched_blocks(self):
+        """ Tests if blocks away from the cut are left unchanged. """
+        blocks = [('a', 0, 2), ('a', 2, 4), ('g', 4, 4), ('b', 10, 15)]
+        space = list_to_linspace(blocks)
+        space.cut(0, 0)
+        test = space.to_list()
+        assert blocks == test, \
+            'Expected remaining blocks {}, instead got {}'.format(blocks, test)
+        space.cut(4, 5)
+        exp = [('a', 0, 2), ('a', 2, 4), ('g', 4, 4), ('b', 11, 15)]
+        test = space.to_list()
+        assert exp == test, \
+            'Expected remaining blocks {}, instead got {}'.format(exp, test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if a range past the end raises IndexError. """
+        self.space.cut(10, 15)
+
+
+class TestBlockSpaceToBitmap:
+    """ Unit tests for exporting a BlockSpace as a coverage bitmap. """
+    def setup(self):
+        self.space = list_to_linspace([('a', 0, 2), ('b', 4, 6)])
+
+    def teardown(self):
+        pass
+
+    def test_gap_between_blocks(self):
+        """ Tests if the region between blocks is False. """
+        exp = [True, True, False, False, True, True]
+        test = self.space.to_bitmap(0, 6)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_outside_blocks(self):
+        """ Tests if coordinates outside all blocks are False. """
+        exp = [False, True, True, False]
+        test = self.space.to_bitmap(-1, 3)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_range(self):
+        """ Tests if lo >= hi raises ValueError. """
+        self.space.to_bitmap(3, 3)
+
+
+class TestBlockSpaceSimplify:
+    """ Unit tests for merging blocks down to a maximum count. """
+    def setup(self):
+        self.space = list_to_linspace(
+            [('s', i * 10, i * 10 + 5 + i) for i in range(10)])
+
+    def teardown(self):
+        pass
+
+    def test_block_count(self):
+        """ Tests if a 10-block space is reduced to 3 blocks. """
+        self.space.simplify(3)
+        exp = 3
+        test = len(self.space.to_list())
+        assert exp == test, 'Expected {} blocks, instead got {}'.format(exp, test)
+
+    def test_coverage_grows(self):
+        """ Tests if positions covered before are still covered. """
+        before = self.space.to_bitmap(0, 120)
+        self.space.simplify(3)
+        after = self.space.to_bitmap(0, 120)
+        test = [i for i, (b, a) in enumerate(zip(before, after)) if b and not a]
+        assert [] == test, 'Expected no uncovered positions, instead got {}'.format(test)
+
+    def test_within_budget(self):
+        """ Tests if a space within the budget is unchanged. """
+        exp = self.space.to_list()
+        self.space.simplify(10)
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid(self):
+        """ Tests if a non-positive max_blocks raises ValueError. """
+        self.space.simplify(0)
+
+
+class TestBlockSpaceScale:
+    """ Unit tests for scaling BlockSpace coordinates. """
+    def setup(self):
+        self.space = list_to_linspace([('a', 0, 2), ('b', 5, 9)])
+
+    def teardown(self):
+        pass
+
+    def test_scale(self):
+        """ Tests if coordinates and length are scaled in place. """
+        length = self.space.len()
+        self.space.scale(3)
+        exp = [('a', 0, 6), ('b', 15, 27)]
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+        exp = length * 3
+        test = self.space.len()
+        assert exp == test, 'Expected length {}, instead got {}'.format(exp, test)
+
+    def test_scaled(self):
+        """ Tests if scaled returns a copy and leaves the original. """
+        exp = [('a', 0, 6), ('b', 15, 27)]
+        test = self.space.scaled(3).to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+        exp = [('a', 0, 2), ('b', 5, 9)]
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_factor(self):
+        """ Tests if a non-positive factor raises ValueError. """
+        self.space.scale(0)
+
+    @raises(OverflowError)
+    def test_overflow(self):
+        """ Tests if coordinates too large to scale raise OverflowError. """
+        list_to_linspace([('a', 0, 2 ** 30)]).scale(3)
+
+
+class TestBlockSpaceInsertGap:
+    """ Unit tests for inserting a gap block into a BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([('a', 0, 5), ('b', 5, 10)])
+
+    def teardown(self):
+        pass
+
+    def test_start(self):
+        """ Tests inserting a gap at the start. """
+        self.space.insert_gap(0, 2)
+        exp = [('g', 0, 2), ('a', 2, 7), ('b', 7, 12)]
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_middle(self):
+        """ Tests inserting a gap within a block. """
+        self.space.insert_gap(3, 2)
+        exp = [('a', 0, 3), ('g', 3, 5), ('a', 5, 7), ('b', 7, 12)]
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_end(self):
+        """ Tests inserting a gap with a custom id at the end. """
+        self.space.insert_gap(10, 2, '-')
+        exp = [('a', 0, 5), ('b', 5, 10), ('-', 10, 12)]
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if a position past the end raises IndexError. """
+        self.space.insert_gap(11, 2)
+
+
+class TestBlockSpaceToListExpand:
+    """ Unit tests for expanding a BlockSpace into per-position tuples. """
+    def setup(self):
+        self.blocks = [('a', 5, 7), ('g', 0, 1), ('b', 10, 12)]
+        self.space = list_to_linspace(self.blocks)
+
+    def teardown(self):
+        pass
+
+    def test_blocks(self):
+        """ Tests if the default output matches the original blocks. """
+        exp = self.blocks
+        test = self.space.to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_expand(self):
+        """ Tests if expand gives one tuple per position. """
+        exp = [(5, 0, 'a'), (6, 1, 'a'), (0, 2, 'g'),
+               (10, 3, 'b'), (11, 4, 'b')]
+        test = self.space.to_list(True)
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_expand_length(self):
+        """ Tests if the expanded length equals len(). """
+        exp = self.space.len()
+        test = len(self.space.to_list(expand=True))
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestBlockSpaceDifference:
+    """ Unit tests for subtracting the blocks of another BlockSpace. """
+    def setup(self):
+        self.space = list_to_linspace([('a', 0, 10), ('b', 20, 30)])
+
+    def teardown(self):
+        pass
+
+    def test_middle(self):
+        """ Tests if removing the middle of a block splits it in two. """
+        other = list_to_linspace([('x', 3, 5)])
+        exp = [('a', 0, 3), ('a', 5, 10), ('b', 20, 30)]
+        test = self.space.difference(other).to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_disjoint(self):
+        """ Tests if a disjoint space leaves the blocks unchanged. """
+        other = list_to_linspace([('x', 40, 50)])
+        exp = self.space.to_list()
+        test = self.space.difference(other).to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_fully_removed(self):
+        """ Tests if an entirely covered block is dropped. """
+        other = list_to_linspace([('x', -5, 12)])
+        exp = [('b', 20, 30)]
+        test = self.space.difference(other).to_list()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
@@ -0,0 +1,1473 @@
+""" Unit tests for the CoordSpace class. """
+
+from nose.tools import *
+
+from libalignmentrs.position import CoordSpace, dedup_spaces, all_same_length
+from libalignmentrs.position import gap_consensus
+from libalignmentrs.position import list_to_linspace
+from libalignmentrs.position import PositionState, SEQUENCE, GAP
+from libalignmentrs.position import from_arrays_batch
+
+
+def block_tuples(blocks):
+    return [(b.id, b.start, b.stop) for b in blocks]
+
+
+class TestLabeledCoordSpace:
+    """ Unit tests for keeping ids through a labeled CoordSpace. """
+    def setup(self):
+        self.data = [10, 11, 12, 13, -1, -1, 20]
+        self.ids = ['exon', 'exon', 'intron', 'intron', 'g', 'g', 'exon']
+        self.space = CoordSpace.from_arrays_labeled(self.data, self.ids)
+
+    def teardown(self):
+        pass
+
+    def test_labels(self):
+        """ Tests if labels returns the original ids. """
+        exp = self.ids
+        test = self.space.labels()
+        assert exp == test, \
+            'Expected labels {}, instead got {}'.format(exp, test)
+
+    def test_unlabeled(self):
+        """ Tests if labels returns None for an unlabeled space. """
+        exp = [None, None, None]
+        test = CoordSpace(0, 3).labels()
+        assert exp == test, \
+            'Expected labels {}, instead got {}'.format(exp, test)
+
+    def test_to_blocks_roundtrip(self):
+        """ Tests if to_blocks restores the original ids. """
+        exp = [('exon', 10, 12), ('intron', 12, 14), ('g', 0, 2),
+               ('exon', 20, 21)]
+        test = block_tuples(self.space.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_to_blocks_repeated_coordinate(self):
+        """ Tests if labeled and unlabeled spaces merge a repeated
+        coordinate the same way. """
+        exp = [('s', 5, 7)]
+        labeled = CoordSpace.from_arrays_labeled([5, 5, 6], ['s', 's', 's'])
+        test = block_tuples(labeled.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+        unlabeled = CoordSpace.from_arrays([5, 5, 6], ['s', 's', 's'])
+        test = block_tuples(unlabeled.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceResample:
+    """ Unit tests for resampling a CoordSpace to a new length. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays(
+            [10, 11, -1, 13, 14], ['s', 's', 'g', 's', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_upsample(self):
+        """ Tests if upsampling keeps the endpoints. """
+        test = self.space.resample(9).to_arrays()[0]
+        assert len(test) == 9, \
+            'Expected length 9, instead got {}'.format(len(test))
+        assert (test[0], test[-1]) == (10, 14), \
+            'Expected endpoints (10, 14), instead got {}'.format(test)
+
+    def test_downsample(self):
+        """ Tests if downsampling maps to the nearest positions. """
+        exp = [10, -1, 14]
+        test = self.space.resample(3).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_length(self):
+        """ Tests if a non-positive target length raises ValueError. """
+        self.space.resample(0)
+
+
+class TestCoordSpaceFindGap:
+    """ Unit tests for finding a gap run of a minimum length. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays(
+            [0, -1, 1, 2, -1, -1, -1, 3],
+            ['s', 'g', 's', 's', 'g', 'g', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_short_gap(self):
+        """ Tests if the first gap run is found. """
+        test = self.space.find_gap(1)
+        assert test == (1, 1), \
+            'Expected (1, 1), instead got {}'.format(test)
+
+    def test_long_gap(self):
+        """ Tests if a shorter gap run is skipped. """
+        test = self.space.find_gap(2)
+        assert test == (4, 3), \
+            'Expected (4, 3), instead got {}'.format(test)
+
+    def test_not_found(self):
+        """ Tests if None is returned when no gap run is long enough. """
+        test = self.space.find_gap(4)
+        assert test is None, \
+            'Expected None, instead got {}'.format(test)
+
+    @raises(ValueError)
+    def test_invalid_length(self):
+        """ Tests if a non-positive minimum length raises ValueError. """
+        self.space.find_gap(0)
+
+
+class TestCoordSpaceRLE:
+    """ Unit tests for run-length encoding a CoordSpace. """
+    def setup(self):
+        self.data = [-1, -1, 10, 11, 12, 15, 16, -1, 20]
+        self.space = CoordSpace.from_arrays(
+            self.data, ['s' if x >= 0 else 'g' for x in self.data])
+
+    def teardown(self):
+        pass
+
+    def test_to_rle(self):
+        """ Tests if to_rle splits runs on gaps and discontinuities. """
+        exp = [(-1, 2, 1), (10, 3, 0), (15, 2, 0), (-1, 1, 1), (20, 1, 0)]
+        test = self.space.to_rle()
+        assert exp == test, \
+            'Expected runs {}, instead got {}'.format(exp, test)
+
+    def test_roundtrip(self):
+        """ Tests if from_rle reconstructs the original coordinates. """
+        test = CoordSpace.from_rle(self.space.to_rle()).to_arrays()[0]
+        assert self.data == test, \
+            'Expected coordinates {}, instead got {}'.format(self.data, test)
+
+
+class TestCoordSpaceWhereCoord:
+    """ Unit tests for selecting positions using a predicate. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays(
+            [10, 11, 12, -1, 13, 14, 15], ['s', 's', 's', 'g', 's', 's', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_range(self):
+        """ Tests selecting coordinates within a range. """
+        exp = [2, 4, 5]
+        test = self.space.where_coord(lambda x: 12 <= x < 15)
+        assert exp == test, \
+            'Expected positions {}, instead got {}'.format(exp, test)
+
+    @raises(ZeroDivisionError)
+    def test_exception(self):
+        """ Tests if exceptions raised by the predicate propagate. """
+        self.space.where_coord(lambda x: 1 / 0)
+
+
+class TestCoordSpaceAlignTo:
+    """ Unit tests for aligning a CoordSpace to a reference gap pattern. """
+    def setup(self):
+        self.reference = CoordSpace.from_arrays(
+            [5, -1, 6, 7, -1, -1, 8], ['s', 'g', 's', 's', 'g', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_align_ungapped(self):
+        """ Tests if gaps are inserted at the reference gap columns. """
+        space = CoordSpace(0, 4)
+        space.align_to(self.reference)
+        exp = [0, -1, 1, 2, -1, -1, 3]
+        test = space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_too_long(self):
+        """ Tests if a space longer than the reference raises ValueError. """
+        CoordSpace(0, 5).align_to(self.reference)
+
+
+class TestCoordSpaceEnumerateCoords:
+    """ Unit tests for listing (index, coordinate) pairs. """
+
+    def test_enumerate_coords(self):
+        """ Tests if pairs match enumerating the coordinate array. """
+        space = CoordSpace.from_arrays([3, 4, -1, 7], ['s', 's', 'g', 's'])
+        exp = list(enumerate(space.to_arrays()[0]))
+        test = space.enumerate_coords()
+        assert exp == test, \
+            'Expected pairs {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceExtractRanges:
+    """ Unit tests for extracting relative ranges from a CoordSpace. """
+    def setup(self):
+        data = [10, 11, 12, -1, -1, 15, 16, 17]
+        self.space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+
+    def teardown(self):
+        pass
+
+    def test_disjoint_ranges(self):
+        """ Tests if ranges are concatenated in the given order. """
+        exp = [15, 16, 10, 11]
+        test = self.space.extract_ranges([(5, 7), (0, 2)]).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_range_with_gap(self):
+        """ Tests extracting a range that includes gaps. """
+        exp = [12, -1, -1, 15]
+        test = self.space.extract_ranges([(2, 6)]).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_overlapping_ranges(self):
+        """ Tests if overlapping ranges raise ValueError. """
+        self.space.extract_ranges([(0, 3), (2, 4)])
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if a range past the end raises IndexError. """
+        self.space.extract_ranges([(6, 9)])
+
+
+class TestCoordSpaceReverseWithIds:
+    """ Unit tests for reversing a CoordSpace and swapping its ids. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays_labeled(
+            [10, 11, -1, 20], ['fwd', 'fwd', 'g', 'rev'])
+
+    def teardown(self):
+        pass
+
+    def test_swap_ids(self):
+        """ Tests if ids are swapped in the resulting blocks. """
+        self.space.reverse_with_ids({'fwd': 'rev', 'rev': 'fwd'})
+        exp = [('fwd', 20, 21), ('g', 0, 1), ('rev', 11, 12),
+               ('rev', 10, 11)]
+        test = block_tuples(self.space.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_no_swap(self):
+        """ Tests if ids are kept when no mapping is given. """
+        self.space.reverse_with_ids(None)
+        exp = ['rev', 'g', 'fwd', 'fwd']
+        test = self.space.labels()
+        assert exp == test, \
+            'Expected labels {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceCoordinateHistogram:
+    """ Unit tests for counting coordinates in bins. """
+    def setup(self):
+        data = [1, 2, -1, 3, 101, 105, -1]
+        self.space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+
+    def teardown(self):
+        pass
+
+    def test_two_bins(self):
+        """ Tests if clustered coordinates are counted in two bins. """
+        exp = {0: 3, 1: 2}
+        test = self.space.coordinate_histogram(100)
+        assert exp == test, \
+            'Expected histogram {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_bin_size(self):
+        """ Tests if a non-positive bin size raises ValueError. """
+        self.space.coordinate_histogram(0)
+
+
+class TestCoordSpaceSequenceBounds:
+    """ Unit tests for the sequence coordinate range of a CoordSpace. """
+
+    def test_unordered(self):
+        """ Tests bounds of sequence coordinates that are out of order. """
+        space = CoordSpace.from_arrays(
+            [15, -1, 3, 9, -1], ['s', 'g', 's', 's', 'g'])
+        exp = (3, 16)
+        test = space.sequence_bounds()
+        assert exp == test, \
+            'Expected bounds {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_all_gaps(self):
+        """ Tests if a space without sequence raises ValueError. """
+        CoordSpace.from_arrays([-1, -1], ['g', 'g']).sequence_bounds()
+
+
+class TestCoordSpaceInterleave:
+    """ Unit tests for interleaving two CoordSpaces. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([0, 1, -1], ['s', 's', 'g'])
+        self.other = CoordSpace.from_arrays([10, -1, 11], ['s', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_interleave(self):
+        """ Tests if positions alternate between the two spaces. """
+        exp = [0, 10, 1, -1, -1, 11]
+        test = self.space.interleave(self.other).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_length_mismatch(self):
+        """ Tests if spaces of different lengths raise ValueError. """
+        self.space.interleave(CoordSpace(0, 2))
+
+
+class TestCoordSpaceGapsAs:
+    """ Unit tests for replacing gaps with a placeholder value. """
+
+    def test_sentinel(self):
+        """ Tests if gaps are replaced and sequence values are kept. """
+        space = CoordSpace.from_arrays([3, -1, 4, -1], ['s', 'g', 's', 'g'])
+        exp = [3, 999999, 4, 999999]
+        test = space.gaps_as(999999)
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceTruncate:
+    """ Unit tests for truncating a CoordSpace. """
+    def setup(self):
+        self.space = CoordSpace(0, 4)
+
+    def teardown(self):
+        pass
+
+    def test_below_length(self):
+        """ Tests truncating to a shorter length. """
+        self.space.truncate(2)
+        exp = [0, 1]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_at_length(self):
+        """ Tests if truncating to the current length keeps the space. """
+        self.space.truncate(4)
+        exp = [0, 1, 2, 3]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_above_length(self):
+        """ Tests if truncating past the end does nothing. """
+        self.space.truncate(10)
+        exp = [0, 1, 2, 3]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_negative_length(self):
+        """ Tests if a negative length raises ValueError. """
+        self.space.truncate(-1)
+
+
+class TestDedupSpaces:
+    """ Unit tests for removing duplicate CoordSpaces from a list. """
+
+    def test_dedup(self):
+        """ Tests if identical spaces map to the same representative. """
+        spaces = [
+            CoordSpace(0, 3),
+            CoordSpace.from_arrays([0, -1, 1], ['s', 'g', 's']),
+            CoordSpace(0, 3),
+        ]
+        unique, index = dedup_spaces(spaces)
+        assert len(unique) == 2, \
+            'Expected 2 unique spaces, instead got {}'.format(len(unique))
+        assert index == [0, 1, 0], \
+            'Expected index [0, 1, 0], instead got {}'.format(index)
+        exp = [0, -1, 1]
+        test = unique[index[1]].to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceSet:
+    """ Unit tests for setting a single coordinate. """
+    def setup(self):
+        self.space = CoordSpace(0, 3)
+
+    def teardown(self):
+        pass
+
+    def test_set_sequence(self):
+        """ Tests setting a sequence coordinate. """
+        self.space.set(0, 7)
+        exp = [7, 1, 2]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_set_gap(self):
+        """ Tests if setting -1 using a negative index makes a gap. """
+        self.space.set(-1, -1)
+        exp = ['s', 's', 'g']
+        test = self.space.to_arrays()[1]
+        assert exp == test, \
+            'Expected ids {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_value(self):
+        """ Tests if a value less than -1 raises ValueError. """
+        self.space.set(0, -2)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if an index past the end raises IndexError. """
+        self.space.set(3, 0)
+
+
+class TestAllSameLength:
+    """ Unit tests for checking that CoordSpaces share a length. """
+
+    def test_uniform(self):
+        """ Tests if the shared length is returned. """
+        spaces = [
+            CoordSpace(0, 3),
+            CoordSpace.from_arrays([0, -1, 1], ['s', 'g', 's']),
+        ]
+        test = all_same_length(spaces)
+        assert test == 3, 'Expected 3, instead got {}'.format(test)
+
+    def test_non_uniform(self):
+        """ Tests if None is returned when lengths differ. """
+        test = all_same_length([CoordSpace(0, 3), CoordSpace(0, 2)])
+        assert test is None, 'Expected None, instead got {}'.format(test)
+
+    def test_empty(self):
+        """ Tests if None is returned for an empty list. """
+        test = all_same_length([])
+        assert test is None, 'Expected None, instead got {}'.format(test)
+
+
+class TestCoordSpaceMaskStr:
+    """ Unit tests for the gap/sequence character mask. """
+    def setup(self):
+        data = [0, 1, 2, -1, -1, 5]
+        self.space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+
+    def teardown(self):
+        pass
+
+    def test_default_chars(self):
+        """ Tests the mask using the default characters. """
+        exp = 'xxx--x'
+        test = self.space.to_mask_str()
+        assert exp == test, \
+            'Expected mask {}, instead got {}'.format(exp, test)
+
+    def test_custom_chars(self):
+        """ Tests the mask using custom characters. """
+        exp = 'SSS..S'
+        test = self.space.to_mask_str('S', '.')
+        assert exp == test, \
+            'Expected mask {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_multi_char(self):
+        """ Tests if a mask character longer than one raises ValueError. """
+        self.space.to_mask_str('xx', '-')
+
+
+class TestCoordSpaceFromMaskStr:
+    """ Unit tests for creating a CoordSpace from a mask string. """
+
+    def test_coordinates(self):
+        """ Tests if sequence positions get incrementing coordinates. """
+        exp = [-1, -1, 10, 11, -1, 12]
+        test = CoordSpace.from_mask_str('--xx-x', 10).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_roundtrip(self):
+        """ Tests if the gap pattern survives a round trip. """
+        mask = 'SS..S.'
+        space = CoordSpace.from_mask_str(mask, 0, 'S', '.')
+        test = space.to_mask_str('S', '.')
+        assert mask == test, \
+            'Expected mask {}, instead got {}'.format(mask, test)
+
+    @raises(ValueError)
+    def test_invalid_char(self):
+        """ Tests if an unexpected character raises ValueError. """
+        CoordSpace.from_mask_str('xx?', 0)
+
+    @raises(ValueError)
+    def test_negative_start(self):
+        """ Tests if a negative start raises ValueError. """
+        CoordSpace.from_mask_str('x-x', -1)
+
+    @raises(ValueError)
+    def test_same_chars(self):
+        """ Tests if identical seq_char and gap_char raise ValueError. """
+        CoordSpace.from_mask_str('xx', 0, 'x', 'x')
+
+    @raises(ValueError)
+    def test_empty_char(self):
+        """ Tests if an empty mask character raises ValueError. """
+        CoordSpace.from_mask_str('xx', 0, '', '-')
+
+
+class TestCoordSpaceSortBlocks:
+    """ Unit tests for sorting the runs of a CoordSpace. """
+
+    def test_sort_blocks(self):
+        """ Tests if blocks are sorted after moving whole runs. """
+        data = [20, 21, 22, -1, 5, 6, 7]
+        space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+        space.sort_blocks()
+        exp = [5, 6, 7, 20, 21, 22, -1]
+        test = space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+        starts = [b.start for b in space.to_blocks() if b.id == 's']
+        assert starts == sorted(starts), \
+            'Expected sorted blocks, instead got {}'.format(space.to_blocks())
+
+
+class TestCoordSpaceRunSummary:
+    """ Unit tests for counting sequence and gap runs. """
+
+    def check_runs(self, data, exp):
+        space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+        test = space.run_summary()
+        assert exp == test, \
+            'Expected runs {}, instead got {}'.format(exp, test)
+
+    def test_internal_gap(self):
+        """ Tests a space with an internal gap. """
+        self.check_runs([0, 1, -1, 2], (2, 1))
+
+    def test_leading_and_trailing_gaps(self):
+        """ Tests a space with leading and trailing gaps. """
+        self.check_runs([-1, 0, 1, -1, -1], (1, 2))
+
+    def test_all_gaps(self):
+        """ Tests a space containing only gaps. """
+        self.check_runs([-1, -1], (0, 1))
+
+
+class TestCoordSpaceToBedgraph:
+    """ Unit tests for bedGraph coverage output. """
+    def setup(self):
+        data = [-1, 10, 11, 12, -1, -1, 20]
+        self.space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+
+    def teardown(self):
+        pass
+
+    def test_with_gaps(self):
+        """ Tests if gap runs are emitted with a value of 0. """
+        exp = '\n'.join([
+            'chr1\t0\t1\t0', 'chr1\t1\t4\t1', 'chr1\t4\t6\t0', 'chr1\t6\t7\t1',
+        ])
+        test = self.space.to_bedgraph('chr1')
+        assert exp == test, \
+            'Expected bedGraph {}, instead got {}'.format(exp, test)
+
+    def test_without_gaps(self):
+        """ Tests if gap runs are omitted. """
+        exp = 'chr1\t1\t4\t1\nchr1\t6\t7\t1'
+        test = self.space.to_bedgraph('chr1', include_gaps=False)
+        assert exp == test, \
+            'Expected bedGraph {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceMapCoords:
+    """ Unit tests for transforming coordinates using a callback. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([1, 2, -1, 3], ['s', 's', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_double(self):
+        """ Tests doubling every coordinate while keeping gaps. """
+        exp = [2, 4, -1, 6]
+        test = self.space.map_coords(lambda x: x * 2).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_negative(self):
+        """ Tests if a negative return value raises ValueError. """
+        self.space.map_coords(lambda x: -x)
+
+
+class TestCoordSpaceSliding:
+    """ Unit tests for iterating over windows of a CoordSpace. """
+    def setup(self):
+        self.space = CoordSpace(0, 6)
+
+    def teardown(self):
+        pass
+
+    def windows(self, *args, **kwargs):
+        return [w.to_arrays()[0] for w in self.space.sliding(*args, **kwargs)]
+
+    def test_step_1(self):
+        """ Tests windows of length 3 advancing by 1. """
+        exp = [[0, 1, 2], [1, 2, 3], [2, 3, 4], [3, 4, 5]]
+        test = self.windows(3, 1)
+        assert exp == test, \
+            'Expected windows {}, instead got {}'.format(exp, test)
+
+    def test_step_2(self):
+        """ Tests if the last partial window is included. """
+        exp = [[0, 1, 2], [2, 3, 4], [4, 5]]
+        test = self.windows(3, 2)
+        assert exp == test, \
+            'Expected windows {}, instead got {}'.format(exp, test)
+
+    def test_step_2_drop_last(self):
+        """ Tests if the last partial window is dropped. """
+        exp = [[0, 1, 2], [2, 3, 4]]
+        test = self.windows(3, 2, drop_last=True)
+        assert exp == test, \
+            'Expected windows {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_invalid_window(self):
+        """ Tests if a non-positive window raises ValueError. """
+        self.space.sliding(0, 1)
+
+
+class TestCoordSpaceIsGapAt:
+    """ Unit tests for classifying single positions. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([0, -1, 2], ['s', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_gap(self):
+        """ Tests a gap position. """
+        assert self.space.is_gap_at(1), 'Expected position 1 to be a gap'
+        assert not self.space.is_sequence_at(1), \
+            'Expected position 1 not to be sequence'
+
+    def test_sequence(self):
+        """ Tests a sequence position using a negative index. """
+        assert self.space.is_sequence_at(-1), \
+            'Expected position -1 to be sequence'
+        assert not self.space.is_gap_at(-1), \
+            'Expected position -1 not to be a gap'
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if an index past the end raises IndexError. """
+        self.space.is_gap_at(3)
+
+
+class TestCoordSpaceCoalesce:
+    """ Unit tests for caching the blocks of a CoordSpace. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays(
+            [0, 1, -1, -1, 5, 6], ['s', 's', 'g', 'g', 's', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_blocks_unchanged(self):
+        """ Tests if coalesce does not change the blocks. """
+        exp = block_tuples(self.space.to_blocks())
+        self.space.coalesce()
+        test = block_tuples(self.space.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+        assert self.space.count_blocks() == len(exp), \
+            'Expected {} blocks, instead got {}'.format(
+                len(exp), self.space.count_blocks())
+
+    def test_cache_cleared(self):
+        """ Tests if modifying the space clears the cache. """
+        self.space.coalesce()
+        self.space.truncate(2)
+        exp = [('s', 0, 2)]
+        test = block_tuples(self.space.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceTile:
+    """ Unit tests for splitting a CoordSpace into fixed-width tiles. """
+    def setup(self):
+        self.data = [0, 1, -1, 3, 4, 5]
+        self.space = CoordSpace.from_arrays(
+            self.data, ['s' if x >= 0 else 'g' for x in self.data])
+
+    def teardown(self):
+        pass
+
+    def test_multiple_of_width(self):
+        """ Tests tiles when the length is a multiple of width. """
+        exp = [[0, 1, -1], [3, 4, 5]]
+        test = [t.to_arrays()[0] for t in self.space.tile(3)]
+        assert exp == test, \
+            'Expected tiles {}, instead got {}'.format(exp, test)
+
+    def test_not_multiple_of_width(self):
+        """ Tests if the last tile is shorter and tiles concatenate to
+        the original. """
+        tiles = [t.to_arrays()[0] for t in self.space.tile(4)]
+        assert len(tiles[-1]) == 2, \
+            'Expected last tile of length 2, instead got {}'.format(tiles)
+        test = [x for t in tiles for x in t]
+        assert self.data == test, \
+            'Expected coordinates {}, instead got {}'.format(self.data, test)
+
+    @raises(ValueError)
+    def test_invalid_width(self):
+        """ Tests if a non-positive width raises ValueError. """
+        self.space.tile(0)
+
+
+class TestCoordSpaceFromMaskAndCoords:
+    """ Unit tests for creating a CoordSpace from a gap mask and
+    coordinates. """
+
+    def test_matching_counts(self):
+        """ Tests if coordinates fill the non-gap positions in order. """
+        space = CoordSpace.from_mask_and_coords(
+            [True, False, False, True, False], [7, 8, 20])
+        exp = [-1, 7, 8, -1, 20]
+        test = space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_mismatching_counts(self):
+        """ Tests if a count mismatch raises ValueError. """
+        CoordSpace.from_mask_and_coords([False, False, True], [1])
+
+
+class TestCoordSpaceCoverageIndex:
+    """ Unit tests for counting sequence positions in relative ranges. """
+    def setup(self):
+        self.data = [-1, 0, 1, -1, -1, 2, 3, -1]
+        self.space = CoordSpace.from_arrays(
+            self.data, ['s' if x >= 0 else 'g' for x in self.data])
+
+    def teardown(self):
+        pass
+
+    def test_against_brute_force(self):
+        """ Tests indexed counts against counting positions directly. """
+        self.space.build_coverage_index()
+        for a, b in [(0, 8), (0, 1), (1, 3), (2, 6), (3, 5), (4, 4)]:
+            exp = len([x for x in self.data[a:b] if x >= 0])
+            test = self.space.range_sequence_count(a, b)
+            assert exp == test, \
+                'Expected {} in {}:{}, instead got {}'.format(exp, a, b, test)
+
+    def test_invalidated(self):
+        """ Tests if modifying the space invalidates the index. """
+        self.space.build_coverage_index()
+        self.space.set(0, 9)
+        test = self.space.range_sequence_count(0, 8)
+        assert test == 5, 'Expected 5, instead got {}'.format(test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if a range past the end raises IndexError. """
+        self.space.range_sequence_count(0, 9)
+
+
+class TestCoordSpacePopPush:
+    """ Unit tests for removing and appending single coordinates. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([1, -1, 2], ['s', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_pop(self):
+        """ Tests if pop returns and removes the last coordinate. """
+        test = self.space.pop()
+        assert test == 2, 'Expected 2, instead got {}'.format(test)
+        exp = [1, -1]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(IndexError)
+    def test_pop_empty(self):
+        """ Tests if popping from an empty space raises IndexError. """
+        space = CoordSpace(0, 0)
+        space.pop()
+
+    def test_push_gap(self):
+        """ Tests pushing a gap. """
+        self.space.push(-1)
+        exp = [1, -1, 2, -1]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_push_invalid(self):
+        """ Tests if pushing a value less than -1 raises ValueError. """
+        self.space.push(-2)
+
+
+class TestCoordSpaceApproxEquals:
+    """ Unit tests for comparing CoordSpaces within a tolerance. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([10, 11, -1, 13], ['s', 's', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_within_tolerance(self):
+        """ Tests if coordinates within tolerance are equal. """
+        other = CoordSpace.from_arrays([11, 10, -1, 14], ['s', 's', 'g', 's'])
+        assert self.space.approx_equals(other, 1), \
+            'Expected spaces to be equal'
+
+    def test_over_tolerance(self):
+        """ Tests if coordinates over tolerance are not equal. """
+        other = CoordSpace.from_arrays([12, 11, -1, 13], ['s', 's', 'g', 's'])
+        assert not self.space.approx_equals(other, 1), \
+            'Expected spaces not to be equal'
+
+    def test_different_gaps(self):
+        """ Tests if different gap patterns are never equal. """
+        other = CoordSpace.from_arrays([10, -1, 12, 13], ['s', 'g', 's', 's'])
+        assert not self.space.approx_equals(other, 100), \
+            'Expected spaces not to be equal'
+
+
+class TestCoordSpaceRemoveRange:
+    """ Unit tests for removing a contiguous range of positions. """
+    def setup(self):
+        data = [0, 1, -1, 3, 4, -1, 6, 7]
+        self.space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+
+    def teardown(self):
+        pass
+
+    def test_interior_range(self):
+        """ Tests if removing a range matches removing each position. """
+        other = self.space.copy()
+        self.space.remove_range(2, 6)
+        other.remove([2, 3, 4, 5])
+        exp = other.to_arrays()[0]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_reversed_range(self):
+        """ Tests if start greater than stop raises ValueError. """
+        self.space.remove_range(4, 2)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if a range past the end raises IndexError. """
+        self.space.remove_range(6, 9)
+
+
+class TestCoordSpaceRemoveMany:
+    """ Unit tests for removing many scattered positions. """
+    def setup(self):
+        self.data = [-1 if x % 13 == 0 else x for x in range(5000)]
+        self.space = CoordSpace.from_arrays(
+            self.data, ['s' if x >= 0 else 'g' for x in self.data])
+        self.positions = [x for x in range(5000) if x % 7 == 3 or x % 11 == 0]
+
+    def teardown(self):
+        pass
+
+    def test_remove(self):
+        """ Tests removing scattered positions from a large space. """
+        removed = set(self.positions)
+        exp = [x for i, x in enumerate(self.data) if i not in removed]
+        self.space.remove(self.positions)
+        test = self.space.to_arrays()[0]
+        assert exp == test, 'Expected remaining coordinates to match'
+
+    def test_retain(self):
+        """ Tests retaining scattered positions from a large space. """
+        kept = set(self.positions)
+        exp = [x for i, x in enumerate(self.data) if i in kept]
+        self.space.retain(self.positions)
+        test = self.space.to_arrays()[0]
+        assert exp == test, 'Expected retained coordinates to match'
+
+
+class TestCoordSpaceRemoveGapsRecording:
+    """ Unit tests for removing gaps while recording their positions. """
+    def setup(self):
+        data = [0, 1, -1, -1, 2, 3, -1, 4]
+        self.space = CoordSpace.from_arrays(
+            data, ['s' if x >= 0 else 'g' for x in data])
+
+    def teardown(self):
+        pass
+
+    def test_two_interior_gaps(self):
+        """ Tests the ungapped space and the recorded gap positions. """
+        exp = [(2, 2), (4, 1)]
+        test = self.space.remove_gaps_recording()
+        assert exp == test, \
+            'Expected gaps {}, instead got {}'.format(exp, test)
+        exp = [0, 1, 2, 3, 4]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceInsertGaps:
+    """ Unit tests for reinserting recorded gaps. """
+    def setup(self):
+        self.data = [-1, 0, 1, -1, -1, 2, 3, -1, 4, -1]
+        self.space = CoordSpace.from_arrays(
+            self.data, ['s' if x >= 0 else 'g' for x in self.data])
+
+    def teardown(self):
+        pass
+
+    def test_roundtrip(self):
+        """ Tests if reinserting recorded gaps restores the space. """
+        self.space.insert_gaps(self.space.remove_gaps_recording())
+        test = self.space.to_arrays()[0]
+        assert self.data == test, \
+            'Expected coordinates {}, instead got {}'.format(self.data, test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if an index past the end raises IndexError. """
+        self.space.insert_gaps([(11, 1)])
+
+
+class TestGapConsensus:
+    """ Unit tests for the per-column gap consensus of CoordSpaces. """
+    def setup(self):
+        rows = [[-1, 0, -1, 1], [-1, -1, -1, 1], [-1, 0, 1, 2]]
+        self.spaces = [
+            CoordSpace.from_arrays(r, ['s' if x >= 0 else 'g' for x in r])
+            for r in rows
+        ]
+
+    def teardown(self):
+        pass
+
+    def test_half(self):
+        """ Tests columns where at least half of the rows are gaps. """
+        exp = [True, False, True, False]
+        test = gap_consensus(self.spaces, 0.5)
+        assert exp == test, \
+            'Expected mask {}, instead got {}'.format(exp, test)
+
+    def test_all(self):
+        """ Tests columns where all rows are gaps. """
+        exp = [True, False, False, False]
+        test = gap_consensus(self.spaces, 1.0)
+        assert exp == test, \
+            'Expected mask {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_length_mismatch(self):
+        """ Tests if spaces of different lengths raise ValueError. """
+        gap_consensus([CoordSpace(0, 3), CoordSpace(0, 2)], 0.5)
+
+
+class TestCoordSpacePaste:
+    """ Unit tests for pasting a CoordSpace into another. """
+    def setup(self):
+        self.space = CoordSpace(0, 3)
+        self.other = CoordSpace.from_arrays([100, -1], ['s', 'g'])
+
+    def teardown(self):
+        pass
+
+    def check_paste(self, index, exp):
+        self.space.paste(index, self.other)
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_start(self):
+        """ Tests pasting at the start. """
+        self.check_paste(0, [100, -1, 0, 1, 2])
+
+    def test_middle(self):
+        """ Tests pasting in the middle. """
+        self.check_paste(2, [0, 1, 100, -1, 2])
+
+    def test_end(self):
+        """ Tests pasting at the end. """
+        self.check_paste(3, [0, 1, 2, 100, -1])
+
+    def test_self(self):
+        """ Tests pasting a space into itself. """
+        self.space.paste(1, self.space)
+        exp = [0, 0, 1, 2, 1, 2]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if an index past the end raises IndexError. """
+        self.space.paste(4, self.other)
+
+
+class TestCoordSpaceAlignStarts:
+    """ Unit tests for padding two spaces to a common start coordinate. """
+    def setup(self):
+        self.a = CoordSpace(2, 5)
+        self.b = CoordSpace(0, 3)
+
+    def teardown(self):
+        pass
+
+    def test_pad_first(self):
+        """ Tests if the space starting higher is padded with gaps. """
+        a, b = CoordSpace.align_starts(self.a, self.b)
+        exp = ([-1, -1, 2, 3, 4], [0, 1, 2])
+        test = (a.to_arrays()[0], b.to_arrays()[0])
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_pad_second(self):
+        """ Tests if padding is applied when the second space starts higher. """
+        b, a = CoordSpace.align_starts(self.b, self.a)
+        exp = ([0, 1, 2], [-1, -1, 2, 3, 4])
+        test = (b.to_arrays()[0], a.to_arrays()[0])
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_unchanged(self):
+        """ Tests if the originals are left unchanged. """
+        CoordSpace.align_starts(self.a, self.b)
+        exp = [2, 3, 4]
+        test = self.a.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_no_sequence(self):
+        """ Tests if a space without sequence raises ValueError. """
+        gaps = CoordSpace.from_arrays([-1, -1], ['g', 'g'])
+        CoordSpace.align_starts(gaps, self.b)
+
+
+class TestCoordSpaceHash:
+    """ Unit tests for hashing frozen CoordSpace objects. """
+    def setup(self):
+        self.a = CoordSpace(0, 5)
+        self.b = CoordSpace(0, 5)
+
+    def teardown(self):
+        pass
+
+    def test_equal_hash(self):
+        """ Tests if equal frozen spaces hash equally. """
+        self.a.freeze()
+        self.b.freeze()
+        assert self.a == self.b, \
+            'Expected {} == {}'.format(self.a, self.b)
+        exp = hash(self.a)
+        test = hash(self.b)
+        assert exp == test, 'Expected hash {}, instead got {}'.format(exp, test)
+
+    def test_dict_key(self):
+        """ Tests if a frozen space can be used as a dict key. """
+        self.a.freeze()
+        self.b.freeze()
+        cache = {self.a: 'value'}
+        exp = 'value'
+        test = cache[self.b]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(TypeError)
+    def test_unfrozen(self):
+        """ Tests if hashing an unfrozen space raises TypeError. """
+        hash(self.a)
+
+
+class TestCoordSpaceFreeze:
+    """ Unit tests for freezing and thawing CoordSpace objects. """
+    def setup(self):
+        self.space = CoordSpace(0, 5)
+        self.space.freeze()
+
+    def teardown(self):
+        pass
+
+    def test_is_frozen(self):
+        """ Tests if freeze marks the space as frozen. """
+        assert self.space.is_frozen(), 'Expected the space to be frozen'
+
+    @raises(RuntimeError)
+    def test_remove(self):
+        """ Tests if removing from a frozen space raises RuntimeError. """
+        self.space.remove([0])
+
+    @raises(RuntimeError)
+    def test_set(self):
+        """ Tests if setting a value in a frozen space raises RuntimeError. """
+        self.space.set(0, 10)
+
+    def test_thaw(self):
+        """ Tests if thawing re-enables mutation. """
+        self.space.thaw()
+        assert not self.space.is_frozen(), 'Expected the space to be thawed'
+        self.space.remove([0])
+        exp = [1, 2, 3, 4]
+        test = self.space.to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceFromBitmap:
+    """ Unit tests for creating a CoordSpace from a bitmap. """
+    def setup(self):
+        self.bitmap = [True, True, False, False, True, True]
+
+    def teardown(self):
+        pass
+
+    def test_from_bitmap(self):
+        """ Tests if True items get incrementing coordinates from start. """
+        exp = [5, 6, -1, -1, 7, 8]
+        test = CoordSpace.from_bitmap(self.bitmap, 5).to_arrays()[0]
+        assert exp == test, \
+            'Expected coordinates {}, instead got {}'.format(exp, test)
+
+    def test_roundtrip(self):
+        """ Tests if sequence positions match a BlockSpace bitmap. """
+        bitmap = list_to_linspace([('a', 0, 2), ('b', 4, 6)]).to_bitmap(0, 6)
+        space = CoordSpace.from_bitmap(bitmap, 0)
+        exp = bitmap
+        test = [space.is_sequence_at(i) for i in range(space.len_all())]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(ValueError)
+    def test_negative_start(self):
+        """ Tests if a negative start raises ValueError. """
+        CoordSpace.from_bitmap(self.bitmap, -1)
+
+
+class TestCoordSpaceGapEntropy:
+    """ Unit tests for the entropy of the gap/sequence distribution. """
+
+    def test_half_gaps(self):
+        """ Tests if a 50/50 space has an entropy of 1.0. """
+        space = CoordSpace.from_arrays([0, -1, 1, -1], ['s', 'g', 's', 'g'])
+        exp = 1.0
+        test = space.gap_entropy()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_all_sequence(self):
+        """ Tests if an all-sequence space has an entropy of 0.0. """
+        exp = 0.0
+        test = CoordSpace(0, 5).gap_entropy()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_empty(self):
+        """ Tests if an empty space has an entropy of 0.0. """
+        exp = 0.0
+        test = CoordSpace(0, 0).gap_entropy()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceCommonLength:
+    """ Unit tests for common prefix and suffix lengths. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([0, 1, -1, 2, 3],
+                                            ['s', 's', 'g', 's', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_prefix_only(self):
+        """ Tests spaces sharing only a prefix. """
+        other = CoordSpace.from_arrays([0, 1, -1, 5], ['s', 's', 'g', 's'])
+        exp = (3, 0)
+        test = (self.space.common_prefix_length(other),
+                self.space.common_suffix_length(other))
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_suffix_only(self):
+        """ Tests spaces sharing only a suffix. """
+        other = CoordSpace.from_arrays([9, 2, 3], ['s', 's', 's'])
+        exp = (0, 2)
+        test = (self.space.common_prefix_length(other),
+                self.space.common_suffix_length(other))
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_equal(self):
+        """ Tests entirely equal spaces. """
+        other = self.space.copy()
+        exp = (5, 5)
+        test = (self.space.common_prefix_length(other),
+                self.space.common_suffix_length(other))
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceSample:
+    """ Unit tests for random sampling of positions with a seed. """
+    def setup(self):
+        self.space = CoordSpace(0, 20)
+
+    def teardown(self):
+        pass
+
+    def test_reproducible(self):
+        """ Tests if the same seed gives the same sample. """
+        exp = self.space.sample(5, 42).to_arrays()[0]
+        test = self.space.sample(5, 42).to_arrays()[0]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_without_replacement(self):
+        """ Tests if sampled positions are distinct. """
+        test = self.space.sample(20, 7).to_arrays()[0]
+        exp = list(range(20))
+        assert exp == sorted(test), \
+            'Expected {}, instead got {}'.format(exp, sorted(test))
+
+    @raises(ValueError)
+    def test_too_large(self):
+        """ Tests if k greater than the length raises ValueError. """
+        self.space.sample(21, 42)
+
+
+class TestCoordSpaceStates:
+    """ Unit tests for PositionState values of CoordSpace positions. """
+    def setup(self):
+        self.space = CoordSpace.from_arrays([0, -1, 1], ['s', 'g', 's'])
+
+    def teardown(self):
+        pass
+
+    def test_to_states(self):
+        """ Tests mapping a mixed space to states. """
+        exp = [SEQUENCE, GAP, SEQUENCE]
+        test = self.space.to_states()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_state_at(self):
+        """ Tests getting the state at a negative index. """
+        test = self.space.state_at(-2)
+        assert isinstance(test, PositionState), \
+            'Expected a PositionState, instead got {}'.format(type(test))
+        assert test.is_gap, 'Expected {}, instead got {}'.format(GAP, test)
+
+    def test_repr(self):
+        """ Tests if the repr matches the module constant names. """
+        exp = ['SEQUENCE', 'GAP']
+        test = [repr(SEQUENCE), repr(GAP)]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    @raises(IndexError)
+    def test_out_of_range(self):
+        """ Tests if an out-of-range index raises IndexError. """
+        self.space.state_at(3)
+
+
+class TestCoordSpaceLongestRuns:
+    """ Unit tests for the longest gap and sequence runs. """
+    def setup(self):
+        coords = [0, -1, -1, 1, 2, 3, -1, -1, -1, 4, 5]
+        ids = ['g' if x == -1 else 's' for x in coords]
+        self.space = CoordSpace.from_arrays(coords, ids)
+
+    def teardown(self):
+        pass
+
+    def test_max_gap_length(self):
+        """ Tests if the longer of two gap runs is returned. """
+        exp = 3
+        test = self.space.max_gap_length()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_longest_sequence_run(self):
+        """ Tests if the longest of the sequence runs is returned. """
+        exp = 3
+        test = self.space.longest_sequence_run()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_no_gaps(self):
+        """ Tests if a space without gaps has a max gap length of 0. """
+        exp = 0
+        test = CoordSpace(0, 5).max_gap_length()
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceRotate:
+    """ Unit tests for cyclically rotating a CoordSpace. """
+    def setup(self):
+        self.coords = [0, 1, -1, 2, 3]
+        ids = ['g' if x == -1 else 's' for x in self.coords]
+        self.space = CoordSpace.from_arrays(self.coords, ids)
+
+    def teardown(self):
+        pass
+
+    def test_positive(self):
+        """ Tests rotating to the left by a positive amount. """
+        self.space.rotate(2)
+        exp = [-1, 2, 3, 0, 1]
+        test = self.space.to_arrays()[0]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_negative(self):
+        """ Tests rotating to the right by a negative amount. """
+        self.space.rotate(-1)
+        exp = [3, 0, 1, -1, 2]
+        test = self.space.to_arrays()[0]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_identity(self):
+        """ Tests if rotating by the length leaves the space unchanged. """
+        self.space.rotate(self.space.len_all())
+        exp = self.coords
+        test = self.space.to_arrays()[0]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+
+class TestCoordSpaceToBlocksMixed:
+    """ Unit tests for to_blocks on unlabeled spaces mixing gaps and
+    sequence. """
+
+    def check_blocks(self, coords, exp):
+        ids = ['g' if x == -1 else 's' for x in coords]
+        space = CoordSpace.from_arrays(coords, ids)
+        test = block_tuples(space.to_blocks())
+        assert exp == test, \
+            'Expected blocks {}, instead got {}'.format(exp, test)
+
+    def test_gap_in_middle(self):
+        """ Tests if blocks switch back to sequence after a gap. """
+        self.check_blocks([0, 1, -1, -1, 4, 5],
+                          [('s', 0, 2), ('g', 0, 2), ('s', 4, 6)])
+
+    def test_leading_gap(self):
+        """ Tests a space starting with a gap. """
+        self.check_blocks([-1, -1, -1, 0, 1],
+                          [('g', 0, 3), ('s', 0, 2)])
+
+    def test_trailing_gap(self):
+        """ Tests a space ending with a gap. """
+        self.check_blocks([0, 1, -1],
+                          [('s', 0, 2), ('g', 0, 1)])
+
+    def test_discontinuous(self):
+        """ Tests sequence jumps around a single gap. """
+        self.check_blocks([3, 4, 8, -1, 9],
+                          [('s', 3, 5), ('s', 8, 9), ('g', 0, 1),
+                           ('s', 9, 10)])
+
+
+class TestCoordSpaceSequenceIntervals:
+    """ Unit tests for extracting sequence intervals. """
+
+    def check_intervals(self, coords, exp):
+        ids = ['g' if x == -1 else 's' for x in coords]
+        test = CoordSpace.from_arrays(coords, ids).sequence_intervals()
+        assert exp == test, \
+            'Expected intervals {}, instead got {}'.format(exp, test)
+
+    def test_interior_gap(self):
+        """ Tests if an interior gap separates intervals. """
+        self.check_intervals([10, 11, 12, -1, 15], [(10, 13), (15, 16)])
+
+    def test_gap_between_contiguous(self):
+        """ Tests if a gap splits otherwise contiguous coordinates. """
+        self.check_intervals([0, 1, -1, 2, 3], [(0, 2), (2, 4)])
+
+    def test_noncontiguous(self):
+        """ Tests if jumps in coordinates split intervals. """
+        self.check_intervals([0, 1, 5, 6, 3], [(0, 2), (5, 7), (3, 4)])
+
+
+class TestCoordSpaceAssertMonotonic:
+    """ Unit tests for checking strictly increasing coordinates. """
+
+    def test_monotonic(self):
+        """ Tests if increasing coordinates with gaps pass. """
+        space = CoordSpace.from_arrays([0, -1, 1, 5, -1, 6],
+                                       ['s', 'g', 's', 's', 'g', 's'])
+        space.assert_monotonic()
+
+    def test_decrease(self):
+        """ Tests if a repeated coordinate raises with its index. """
+        space = CoordSpace.from_arrays([0, -1, 2, -1, 2, 3],
+                                       ['s', 'g', 's', 'g', 's', 's'])
+        try:
+            space.assert_monotonic()
+        except ValueError as e:
+            assert 'index 4' in str(e), \
+                'Expected the error to name index 4, instead got {}'.format(e)
+        else:
+            raise AssertionError('Expected ValueError')
+
+
+class TestFromArraysBatch:
+    """ Unit tests for creating many CoordSpaces at once. """
+
+    def test_valid(self):
+        """ Tests if one space is created per row. """
+        rows = [([0, 1, -1], ['s', 's', 'g']), ([5, 6], ['s', 's'])]
+        exp = [[0, 1, -1], [5, 6]]
+        test = [space.to_arrays()[0] for space in from_arrays_batch(rows)]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_invalid_row(self):
+        """ Tests if the error names the invalid row. """
+        rows = [([0], ['s']), ([0, 1], ['s', 'x'])]
+        try:
+            from_arrays_batch(rows)
+        except ValueError as e:
+            assert 'row 1' in str(e), \
+                'Expected the error to name row 1, instead got {}'.format(e)
+        else:
+            raise AssertionError('Expected ValueError')
+
+
+class TestCoordSpaceCropToSequence:
+    """ Unit tests for cropping a CoordSpace to its sequence span. """
+
+    def test_padded(self):
+        """ Tests if gaps at both ends are removed and interior gaps kept. """
+        coords = [-1, -1, 0, -1, 1, -1]
+        ids = ['g' if x == -1 else 's' for x in coords]
+        space = CoordSpace.from_arrays(coords, ids)
+        space.crop_to_sequence()
+        exp = [0, -1, 1]
+        test = space.to_arrays()[0]
+        assert exp == test, 'Expected {}, instead got {}'.format(exp, test)
+
+    def test_all_gaps(self):
+        """ Tests if an all-gap space becomes empty. """
+        space = CoordSpace.from_arrays([-1, -1], ['g', 'g'])
+        space.crop_to_sequence()
+        exp = 0
+        test = space.len_all()
+        assert exp == test, 'Expected length {}, instead got {}'.format(exp, test)
@@ -1,10 +1,20 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use pyo3::prelude::*;
-use pyo3::{PyObjectProtocol, exceptions};
+use pyo3::{PyObjectProtocol, PySequenceProtocol, PyIterProtocol, exceptions};
+use pyo3::types::{PyBytes, PyObjectRef, PySlice};
+use pyo3::class::CompareOp;
 
 use regex::Regex;
 
+// Operations allowed in a CIGAR string
+const CIGAR_OPS: [&str; 9] = ["M", "I", "D", "N", "S", "H", "P", "=", "X"];
+
 #[pyclass(subclass)]
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 /// Block(id, start, stop, /)
 /// --
 /// 
@@ -25,14 +35,18 @@ pub struct Block {
 #[pymethods]
 impl Block {
     #[new]
+    #[args(strict = "false")]
     /// Creates a new Block object from an id, and start and stop
-    /// coordinates.
-    fn __new__(obj: &PyRawObject, id: &str, start: i32, stop: i32) -> PyResult<()> {
+    /// coordinates. If strict is True, zero-length blocks are rejected.
+    fn __new__(obj: &PyRawObject, id: &str, start: i32, stop: i32, strict: bool) -> PyResult<()> {
         if start > stop {
             return Err(exceptions::ValueError::py_err(
                 format!("start must be less than stop: {} !< {}",
                         start, stop)))
         }
+        if strict && start == stop {
+            return Err(exceptions::ValueError::py_err("zero-length block"))
+        }
         obj.init(|_| {
             let id = id.to_string();
             Block { id, start, stop }
@@ -50,6 +64,12 @@ impl Block {
         Ok(false)
     }
 
+    #[getter]
+    /// Returns the start and stop of the block as a (start, stop) tuple.
+    fn coordinates(&self) -> PyResult<(i32, i32)> {
+        Ok((self.start, self.stop))
+    }
+
     /// to_array()
     ///
     /// Converts the block into a list of positions.
@@ -57,6 +77,288 @@ impl Block {
         Ok((self.start..self.stop).collect::<Vec<i32>>())
     }
 
+    /// midpoint()
+    ///
+    /// Returns the midpoint of the block as a float.
+    /// A zero-length block returns its start coordinate.
+    fn midpoint(&self) -> PyResult<f64> {
+        Ok((self.start as f64 + self.stop as f64) / 2.0)
+    }
+
+    /// center_int()
+    ///
+    /// Returns the midpoint of the block rounded down to an integer.
+    fn center_int(&self) -> PyResult<i32> {
+        Ok(((self.start as f64 + self.stop as f64) / 2.0).floor() as i32)
+    }
+
+    /// approx_eq(other, tol)
+    ///
+    /// Returns True if both blocks have the same id and their start
+    /// and stop coordinates each differ by at most tol.
+    /// Otherwise, returns False.
+    fn approx_eq(&self, other: &Block, tol: i32) -> PyResult<bool> {
+        Ok(self.id == other.id &&
+           (self.start - other.start).abs() <= tol &&
+           (self.stop - other.stop).abs() <= tol)
+    }
+
+    /// longer_than(other)
+    ///
+    /// Returns True if the block is longer than the other block.
+    /// Otherwise, returns False.
+    fn longer_than(&self, other: &Block) -> PyResult<bool> {
+        Ok(self.stop - self.start > other.stop - other.start)
+    }
+
+    /// distance_to(other)
+    ///
+    /// Returns the number of positions strictly between the block and
+    /// the other block. Returns 0 if the blocks overlap or abut, rather
+    /// than a negative value; use overlap_length to measure how much
+    /// two blocks overlap. Ids are ignored.
+    fn distance_to(&self, other: &Block) -> PyResult<i32> {
+        if self.stop <= other.start {
+            return Ok(other.start - self.stop)
+        }
+        if other.stop <= self.start {
+            return Ok(self.start - other.stop)
+        }
+        Ok(0)
+    }
+
+    /// overlaps_with(other)
+    ///
+    /// Returns True if the block and the other block share at least
+    /// one position. Blocks that only abut do not overlap.
+    /// Ids are ignored.
+    fn overlaps_with(&self, other: &Block) -> PyResult<bool> {
+        Ok(self.overlap_length(other)? > 0)
+    }
+
+    /// overlap_length(other)
+    ///
+    /// Returns the number of positions shared by the block and the
+    /// other block, or 0 if they do not overlap. Ids are ignored.
+    fn overlap_length(&self, other: &Block) -> PyResult<i32> {
+        let start = if self.start > other.start { self.start } else { other.start };
+        let stop = if self.stop < other.stop { self.stop } else { other.stop };
+        Ok(if stop > start { stop - start } else { 0 })
+    }
+
+    /// contains_block(other)
+    ///
+    /// Returns True if the other block lies entirely within the block.
+    /// A block contains itself. Ids are ignored.
+    fn contains_block(&self, other: &Block) -> PyResult<bool> {
+        Ok(self.start <= other.start && other.stop <= self.stop)
+    }
+
+    /// split_at(pos)
+    ///
+    /// Splits the block at the absolute position pos and returns the
+    /// two resulting blocks as a tuple. Both blocks keep the id.
+    /// pos must be strictly between start and stop.
+    fn split_at(&self, pos: i32) -> PyResult<(Block, Block)> {
+        if pos <= self.start || pos >= self.stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("split position must be between start and stop: {} !< {} !< {}",
+                        self.start, pos, self.stop)))
+        }
+        Ok((Block{ id: self.id.to_string(), start: self.start, stop: pos },
+            Block{ id: self.id.to_string(), start: pos, stop: self.stop }))
+    }
+
+    /// union(other)
+    ///
+    /// Returns the smallest block covering both blocks, using the id
+    /// of this block.
+    fn union(&self, other: &Block) -> PyResult<Block> {
+        Ok(Block{
+            id: self.id.to_string(),
+            start: if self.start < other.start { self.start } else { other.start },
+            stop: if self.stop > other.stop { self.stop } else { other.stop },
+        })
+    }
+
+    /// with_id(id)
+    ///
+    /// Returns a copy of the block with a new id and the same
+    /// coordinates.
+    fn with_id(&self, id: &str) -> PyResult<Block> {
+        Ok(Block{ id: id.to_string(), start: self.start, stop: self.stop })
+    }
+
+    /// scale(factor)
+    ///
+    /// Returns a new block with start and stop multiplied by factor,
+    /// keeping the id. For example, a factor of 3 converts codon
+    /// coordinates to nucleotide coordinates. Raises OverflowError if a
+    /// scaled coordinate does not fit in a 32-bit integer.
+    fn scale(&self, factor: i32) -> PyResult<Block> {
+        if factor <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("factor must be greater than 0: {}", factor)))
+        }
+        match (self.start.checked_mul(factor), self.stop.checked_mul(factor)) {
+            (Some(start), Some(stop)) => Ok(Block{ id: self.id.to_string(), start, stop }),
+            _ => Err(exceptions::OverflowError::py_err(
+                format!("scaled coordinates are out of range: factor {}", factor))),
+        }
+    }
+
+    /// clip_to_nonnegative()
+    ///
+    /// Returns a copy of the block with start clamped to 0.
+    /// Returns None if the entire block is negative.
+    fn clip_to_nonnegative(&self) -> PyResult<Option<Block>> {
+        if self.stop <= 0 {
+            return Ok(None)
+        }
+        Ok(Some(Block{
+            id: self.id.to_string(),
+            start: if self.start < 0 { 0 } else { self.start },
+            stop: self.stop,
+        }))
+    }
+
+    #[args(drop_last = "false")]
+    /// windows(window, step, drop_last=False)
+    ///
+    /// Returns an iterator over sub-blocks of the given length, advancing
+    /// by step positions. Each sub-block keeps the id of the block.
+    /// The last sub-block may be shorter than window unless drop_last
+    /// is True.
+    fn windows(&self, window: i32, step: i32, drop_last: bool) -> PyResult<Py<BlockWindowIterator>> {
+        if window <= 0 || step <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("window and step must be greater than 0: {}, {}", window, step)))
+        }
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        Py::new(py, |token| BlockWindowIterator{
+            block: self.clone(),
+            window,
+            step,
+            drop_last,
+            pos: self.start,
+            done: false,
+            token,
+        })
+    }
+
+    #[staticmethod]
+    /// sort_by_length(blocks, descending)
+    ///
+    /// Returns a new list of blocks sorted by length.
+    /// Blocks of the same length keep their input order.
+    fn sort_by_length(blocks: Vec<&Block>, descending: bool) -> PyResult<Vec<Block>> {
+        let mut blocks: Vec<Block> = blocks.into_iter().cloned().collect();
+        if descending {
+            blocks.sort_by_key(|b| std::cmp::Reverse(b.stop - b.start));
+        } else {
+            blocks.sort_by_key(|b| b.stop - b.start);
+        }
+        Ok(blocks)
+    }
+
+    #[staticmethod]
+    /// from_slice(id, slice, length)
+    ///
+    /// Returns a new block from the start and stop of a slice.
+    /// None and negative indices are resolved against length.
+    /// Slices with a step other than 1 are not supported.
+    fn from_slice(id: &str, s: &PySlice, length: i32) -> PyResult<Block> {
+        let indices = s.indices(length as std::os::raw::c_long)?;
+        if indices.step != 1 {
+            return Err(exceptions::ValueError::py_err(
+                format!("slice step must be 1: {}", indices.step)))
+        }
+        let start = indices.start as i32;
+        let stop = indices.stop as i32;
+        if start > stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must be less than stop: {} !< {}",
+                        start, stop)))
+        }
+        Ok(Block{ id: id.to_string(), start, stop })
+    }
+
+    /// to_tuple()
+    ///
+    /// Returns the block as an (id, start, stop) tuple.
+    fn to_tuple(&self) -> PyResult<(String, i32, i32)> {
+        Ok((self.id.to_string(), self.start, self.stop))
+    }
+
+    #[staticmethod]
+    /// from_tuple(t)
+    ///
+    /// Returns a new block from an (id, start, stop) tuple.
+    fn from_tuple(t: (String, i32, i32)) -> PyResult<Block> {
+        let (id, start, stop) = t;
+        if start > stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must be less than stop: {} !< {}",
+                        start, stop)))
+        }
+        Ok(Block{ id, start, stop })
+    }
+
+    /// to_bytes()
+    ///
+    /// Encodes the block as a binary record containing start and stop
+    /// as little-endian 32-bit integers, followed by the byte length
+    /// of the id as a little-endian 32-bit integer and the UTF-8 id.
+    fn to_bytes(&self) -> PyResult<Py<PyBytes>> {
+        let id = self.id.as_bytes();
+        let mut data: Vec<u8> = Vec::with_capacity(12 + id.len());
+        data.extend_from_slice(&self.start.to_le_bytes());
+        data.extend_from_slice(&self.stop.to_le_bytes());
+        data.extend_from_slice(&(id.len() as u32).to_le_bytes());
+        data.extend_from_slice(id);
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        Ok(PyBytes::new(py, &data))
+    }
+
+    #[staticmethod]
+    /// from_bytes(data)
+    ///
+    /// Returns a new block decoded from a binary record created
+    /// by to_bytes.
+    fn from_bytes(data: &PyBytes) -> PyResult<Block> {
+        let data = data.as_bytes();
+        if data.len() < 12 {
+            return Err(exceptions::ValueError::py_err(
+                format!("record is too short: {} bytes", data.len())))
+        }
+        let read_word = |i: usize| {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(&data[i..i+4]);
+            buf
+        };
+        let start = i32::from_le_bytes(read_word(0));
+        let stop = i32::from_le_bytes(read_word(4));
+        let id_len = u32::from_le_bytes(read_word(8)) as usize;
+        if data.len() != 12 + id_len {
+            return Err(exceptions::ValueError::py_err(
+                format!("record length does not match id length: {} != {}",
+                        data.len(), 12 + id_len)))
+        }
+        let id = match String::from_utf8(data[12..].to_vec()) {
+            Ok(id) => id,
+            Err(_) => return Err(exceptions::ValueError::py_err(
+                "id is not valid UTF-8"))
+        };
+        if start > stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must be less than stop: {} !< {}",
+                        start, stop)))
+        }
+        Ok(Block{ id, start, stop })
+    }
+
     // Formatting methods
 
     /// to_compressed_str()
@@ -74,10 +376,92 @@ impl Block {
     fn to_extended_str(&self) -> PyResult<String> {
         Ok(format!("{}={}:{}", self.id, self.start, self.stop))
     }
-    
-    // TODO: Add a method to convert to CIGAR string
-    // fn to_cigar_str(&self) -> PyResult<String> {
-    // }
+
+    /// to_gff3(seqid, source, feature_type)
+    ///
+    /// Converts block into a single GFF3 line using 1-based inclusive
+    /// coordinates. Empty fields are written as ".".
+    fn to_gff3(&self, seqid: &str, source: &str, feature_type: &str) -> PyResult<String> {
+        let field = |s: &str| if s.is_empty() { ".".to_string() } else { s.to_string() };
+        let attributes = if self.id.is_empty() {
+            ".".to_string()
+        } else {
+            format!("ID={}", self.id)
+        };
+        Ok(format!("{}\t{}\t{}\t{}\t{}\t.\t.\t.\t{}",
+                   field(seqid), field(source), field(feature_type),
+                   self.start + 1, self.stop, attributes))
+    }
+
+    #[args(offset = "None")]
+    /// to_cigar_str(offset=None)
+    ///
+    /// Converts block into a CIGAR operation using the id as the
+    /// operation letter and the block length as the count.
+    /// A zero-length block returns an empty string.
+    /// When chaining consecutive blocks into a CIGAR string, pass the
+    /// stop of the previous block as offset to check that this block
+    /// starts where the previous one ended.
+    fn to_cigar_str(&self, offset: Option<i32>) -> PyResult<String> {
+        if !CIGAR_OPS.contains(&self.id.as_str()) {
+            return Err(exceptions::ValueError::py_err(
+                format!("invalid CIGAR operation: {}", self.id)))
+        }
+        if let Some(offset) = offset {
+            if offset != self.start {
+                return Err(exceptions::ValueError::py_err(
+                    format!("block does not start at offset: {} != {}", self.start, offset)))
+            }
+        }
+        if self.stop == self.start {
+            return Ok(String::new())
+        }
+        Ok(format!("{}{}", self.stop - self.start, self.id))
+    }
+
+    #[staticmethod]
+    /// from_cigar_str(cigar_op, reference_offset)
+    ///
+    /// Returns a block from a single CIGAR operation such as "45M".
+    /// The id is set to the operation letter, and the block starts at
+    /// reference_offset and spans the length of the operation.
+    /// Raises OverflowError if the block would end past the largest
+    /// 32-bit integer.
+    fn from_cigar_str(cigar_op: &str, reference_offset: i32) -> PyResult<Block> {
+        let invalid = || exceptions::ValueError::py_err(
+            format!("invalid CIGAR operation: {}", cigar_op));
+        if cigar_op.len() < 2 || !cigar_op.is_char_boundary(cigar_op.len() - 1) {
+            return Err(invalid())
+        }
+        let (count, op) = cigar_op.split_at(cigar_op.len() - 1);
+        if !CIGAR_OPS.contains(&op) || !count.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid())
+        }
+        let length: i32 = count.parse().map_err(|_| invalid())?;
+        if length == 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("CIGAR operation has a length of 0: {}", cigar_op)))
+        }
+        match reference_offset.checked_add(length) {
+            Some(stop) => Ok(Block{ id: op.to_string(), start: reference_offset, stop }),
+            None => Err(exceptions::OverflowError::py_err(
+                format!("CIGAR operation is out of range at offset {}: {}",
+                        reference_offset, cigar_op))),
+        }
+    }
+}
+
+// Blocks are ordered by start, then stop, then id.
+impl Ord for Block {
+    fn cmp(&self, other: &Block) -> Ordering {
+        (self.start, self.stop, &self.id).cmp(&(other.start, other.stop, &other.id))
+    }
+}
+
+impl PartialOrd for Block {
+    fn partial_cmp(&self, other: &Block) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 #[pyproto]
@@ -93,6 +477,78 @@ impl PyObjectProtocol for Block {
             Err(x) => Err(x)
         }
     }
+
+    fn __richcmp__(&self, other: &Block, op: CompareOp) -> PyResult<bool> {
+        match op {
+            CompareOp::Eq => Ok(self == other),
+            CompareOp::Ne => Ok(self != other),
+            CompareOp::Lt => Ok(self < other),
+            CompareOp::Le => Ok(self <= other),
+            CompareOp::Gt => Ok(self > other),
+            CompareOp::Ge => Ok(self >= other),
+        }
+    }
+
+    /// Blocks are mutable and compare by value, so like mutable Python
+    /// types that define __eq__, they cannot be hashed.
+    fn __hash__(&self) -> PyResult<isize> {
+        Err(exceptions::TypeError::py_err("unhashable type: 'Block'"))
+    }
+}
+
+#[pyproto]
+impl PySequenceProtocol for Block {
+    fn __contains__(&self, item: i32) -> PyResult<bool> {
+        self.in_block(item)
+    }
+}
+
+#[pyclass]
+/// Iterator over fixed-length sub-blocks of a Block.
+pub struct BlockWindowIterator {
+
+    block: Block,
+    window: i32,
+    step: i32,
+    drop_last: bool,
+    pos: i32,
+    done: bool,
+
+    token: PyToken,
+
+}
+
+impl BlockWindowIterator {
+    /// Returns the next sub-block, or None if there are no more windows.
+    fn next_window(&mut self) -> Option<Block> {
+        if self.done || self.pos >= self.block.stop {
+            return None
+        }
+        let mut stop = self.pos + self.window;
+        if stop >= self.block.stop {
+            self.done = true;
+            if stop > self.block.stop {
+                if self.drop_last {
+                    return None
+                }
+                stop = self.block.stop;
+            }
+        }
+        let window = Block{ id: self.block.id.to_string(), start: self.pos, stop };
+        self.pos += self.step;
+        Some(window)
+    }
+}
+
+#[pyproto]
+impl PyIterProtocol for BlockWindowIterator {
+    fn __iter__(&mut self) -> PyResult<PyObject> {
+        Ok(self.into())
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Block>> {
+        Ok(self.next_window())
+    }
 }
 
 #[pyclass(subclass)]
@@ -133,6 +589,106 @@ impl PyObjectProtocol for BlockSpace {
     }
 }
 
+#[pyproto]
+impl PySequenceProtocol for BlockSpace {
+    fn __contains__(&self, item: &PyObjectRef) -> PyResult<bool> {
+        if let Ok(block) = item.extract::<&Block>() {
+            return Ok(self.covers(block.start, block.stop))
+        }
+        if let Ok(i) = item.extract::<i32>() {
+            return Ok(self.covers(i, i + 1))
+        }
+        Err(exceptions::TypeError::py_err(
+            "only int and Block are supported by the in operator"))
+    }
+}
+
+impl BlockSpace {
+    /// Returns true if every position from start to stop is covered
+    /// by the blocks of the linear space.
+    fn covers(&self, start: i32, stop: i32) -> bool {
+        if start >= stop {
+            return self.covers(start, start + 1)
+        }
+        let mut blocks: Vec<(i32, i32)> = self.coords.iter()
+            .map(|(_, b_start, b_stop)| (*b_start, *b_stop))
+            .collect();
+        blocks.sort_unstable();
+        let mut pos = start;
+        for (b_start, b_stop) in blocks {
+            if b_start <= pos && pos < b_stop {
+                pos = b_stop;
+            }
+            if pos >= stop {
+                return true
+            }
+        }
+        false
+    }
+
+    /// Returns the blocks covering the relative range from start to stop,
+    /// splitting blocks at the range boundaries.
+    fn slice_range(&self, start: i32, stop: i32) -> Vec<(String, i32, i32)> {
+        let mut coords: Vec<(String, i32, i32)> = Vec::new();
+        let mut offset = 0;
+        for (id, b_start, b_stop) in self.coords.iter() {
+            let length = b_stop - b_start;
+            let lo = if start > offset { start } else { offset };
+            let hi = if stop < offset + length { stop } else { offset + length };
+            if lo < hi {
+                coords.push((id.to_string(), b_start + lo - offset, b_start + hi - offset));
+            }
+            offset += length;
+            if offset >= stop {
+                break
+            }
+        }
+        coords
+    }
+
+    /// Returns one (coordinate, relative index, id) tuple for every
+    /// position in the linear space.
+    fn expanded_list(&self) -> Vec<(i32, i32, String)> {
+        let mut list: Vec<(i32, i32, String)> = Vec::new();
+        for (id, start, stop) in self.coords.iter() {
+            for coord in *start..*stop {
+                list.push((coord, list.len() as i32, id.to_string()));
+            }
+        }
+        list
+    }
+
+    /// Returns the number of positions covered by blocks that fall
+    /// within the range from start to stop.
+    fn overlap_length(&self, start: i32, stop: i32) -> i32 {
+        let mut length = 0;
+        for (_, b_start, b_stop) in self.coords.iter() {
+            let lo = if *b_start > start { *b_start } else { start };
+            let hi = if *b_stop < stop { *b_stop } else { stop };
+            if lo < hi {
+                length += hi - lo;
+            }
+        }
+        length
+    }
+
+    /// Merges consecutive blocks that have the same id and
+    /// are contiguous.
+    fn merge_adjacent(coords: Vec<(String, i32, i32)>) -> Vec<(String, i32, i32)> {
+        let mut merged: Vec<(String, i32, i32)> = Vec::with_capacity(coords.len());
+        for (id, start, stop) in coords {
+            if let Some((last_id, _, last_stop)) = merged.last_mut() {
+                if *last_id == id && *last_stop == start {
+                    *last_stop = stop;
+                    continue
+                }
+            }
+            merged.push((id, start, stop));
+        }
+        merged
+    }
+}
+
 #[pymethods]
 impl BlockSpace {
     #[new]
@@ -182,6 +738,140 @@ impl BlockSpace {
         }
     }
 
+    /// extract_ranges(ranges, /)
+    /// --
+    /// 
+    /// Returns a new BlockSpace containing the given list of
+    /// (start, stop) relative ranges. Ranges are half-open and are
+    /// concatenated in the given order. Blocks are split at range
+    /// boundaries and keep their ids. Where consecutive ranges meet,
+    /// the pieces on either side are merged if they continue each other.
+    fn extract_ranges(&self, ranges: Vec<(i32, i32)>) -> PyResult<BlockSpace> {
+        let length = self.len()?;
+        for (start, stop) in ranges.iter() {
+            if start > stop {
+                return Err(exceptions::ValueError::py_err(
+                    format!("start must be less than stop: {} !< {}",
+                            start, stop)))
+            }
+            if *start < 0 || *stop > length {
+                return Err(exceptions::IndexError::py_err(
+                    format!("range out of range: {}:{}", start, stop)))
+            }
+        }
+        let mut sorted_ranges = ranges.clone();
+        sorted_ranges.sort_unstable();
+        for i in 1..sorted_ranges.len() {
+            if sorted_ranges[i].0 < sorted_ranges[i-1].1 {
+                return Err(exceptions::ValueError::py_err(
+                    format!("ranges overlap: {}:{} and {}:{}",
+                            sorted_ranges[i-1].0, sorted_ranges[i-1].1,
+                            sorted_ranges[i].0, sorted_ranges[i].1)))
+            }
+        }
+        let mut coords: Vec<(String, i32, i32)> = Vec::new();
+        for (start, stop) in ranges.iter() {
+            let mut pieces = self.slice_range(*start, *stop);
+            // Merge the pieces on either side of the seam
+            if let (Some(last), Some(first)) = (coords.last_mut(), pieces.first()) {
+                if last.0 == first.0 && last.2 == first.1 {
+                    last.2 = first.2;
+                    pieces.remove(0);
+                }
+            }
+            coords.append(&mut pieces);
+        }
+        Ok(BlockSpace{ coords })
+    }
+
+    /// overlay(other, priority, /)
+    /// --
+    /// 
+    /// Returns a new BlockSpace combining the blocks of both spaces.
+    /// Where blocks overlap, the id that appears first in the priority
+    /// list is kept. Ids not in the list rank last, and ties are won
+    /// by this space. The result is sorted and non-overlapping.
+    fn overlay(&self, other: &BlockSpace, priority: Vec<String>) -> PyResult<BlockSpace> {
+        let rank = |id: &str| priority.iter().position(|x| x == id)
+            .unwrap_or(priority.len());
+        let blocks: Vec<&(String, i32, i32)> = self.coords.iter()
+            .chain(other.coords.iter())
+            .collect();
+        let mut points: Vec<i32> = blocks.iter()
+            .flat_map(|(_, start, stop)| vec![*start, *stop])
+            .collect();
+        points.sort_unstable();
+        points.dedup();
+        let mut coords: Vec<(String, i32, i32)> = Vec::new();
+        for i in 1..points.len() {
+            let (lo, hi) = (points[i-1], points[i]);
+            let winner = blocks.iter()
+                .filter(|(_, start, stop)| *start <= lo && hi <= *stop)
+                .min_by_key(|(id, _, _)| rank(id));
+            if let Some((id, _, _)) = winner {
+                coords.push((id.to_string(), lo, hi));
+            }
+        }
+        Ok(BlockSpace{ coords: BlockSpace::merge_adjacent(coords) })
+    }
+
+    /// difference(other, /)
+    /// --
+    /// 
+    /// Returns a new BlockSpace covering the absolute positions of this
+    /// space that are not covered by any block of the other space.
+    /// Blocks keep their ids and are split where positions are removed.
+    /// Blocks that are entirely removed are dropped.
+    fn difference(&self, other: &BlockSpace) -> PyResult<BlockSpace> {
+        let mut removed: Vec<(i32, i32)> = other.coords.iter()
+            .filter(|(_, start, stop)| start < stop)
+            .map(|(_, start, stop)| (*start, *stop))
+            .collect();
+        removed.sort_unstable();
+        let mut coords: Vec<(String, i32, i32)> = Vec::new();
+        for (id, start, stop) in self.coords.iter() {
+            if start == stop {
+                coords.push((id.to_string(), *start, *stop));
+                continue
+            }
+            let mut pos = *start;
+            for (r_start, r_stop) in removed.iter() {
+                if *r_start >= *stop {
+                    break
+                }
+                if *r_stop <= pos {
+                    continue
+                }
+                if *r_start > pos {
+                    coords.push((id.to_string(), pos, *r_start));
+                }
+                pos = *r_stop;
+                if pos >= *stop {
+                    break
+                }
+            }
+            if pos < *stop {
+                coords.push((id.to_string(), pos, *stop));
+            }
+        }
+        Ok(BlockSpace{ coords })
+    }
+
+    /// split_by_id()
+    /// --
+    /// 
+    /// Returns a dictionary mapping each block id to a new BlockSpace
+    /// containing only the blocks with that id.
+    fn split_by_id(&self) -> PyResult<HashMap<String, BlockSpace>> {
+        let mut spaces: HashMap<String, BlockSpace> = HashMap::new();
+        for (id, start, stop) in self.coords.iter() {
+            spaces.entry(id.to_string())
+                .or_insert_with(|| BlockSpace{ coords: Vec::new() })
+                .coords.push((id.to_string(), *start, *stop));
+        }
+        Ok(spaces)
+    }
+
     /// extract_blocks(ids, /)
     /// --
     /// 
@@ -210,6 +900,7 @@ impl BlockSpace {
     /// --
     /// 
     /// Removes points based on a list of relative positions.
+    /// The remaining positions are kept in ascending order, see retain.
     fn remove(&mut self, positions: Vec<i32>) -> PyResult<()> {
         // Check if positions list is empty or not using max()
         if let Some(max) = positions.iter().max() {
@@ -228,6 +919,132 @@ impl BlockSpace {
         Ok(())
     }
 
+    /// cut(start, stop, /)
+    /// --
+    /// 
+    /// Removes the half-open relative range from start to stop and
+    /// returns it as a new BlockSpace. Blocks are split at the range
+    /// boundaries. If the blocks on either side of the cut have the
+    /// same id and become contiguous, they are merged. Other blocks,
+    /// including empty blocks outside the range, are left unchanged.
+    fn cut(&mut self, start: i32, stop: i32) -> PyResult<BlockSpace> {
+        let length = self.len()?;
+        if start > stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must be less than stop: {} !< {}",
+                        start, stop)))
+        }
+        if start < 0 || stop > length {
+            return Err(exceptions::IndexError::py_err(
+                format!("range out of range: {}:{}", start, stop)))
+        }
+        if start == stop {
+            return Ok(BlockSpace{ coords: Vec::new() })
+        }
+        let mut left: Vec<(String, i32, i32)> = Vec::new();
+        let mut removed: Vec<(String, i32, i32)> = Vec::new();
+        let mut right: Vec<(String, i32, i32)> = Vec::new();
+        let mut offset = 0;
+        for (id, b_start, b_stop) in self.coords.iter() {
+            let (lo, hi) = (offset, offset + b_stop - b_start);
+            offset = hi;
+            if lo == hi {
+                if lo <= start {
+                    left.push((id.to_string(), *b_start, *b_stop));
+                } else if lo >= stop {
+                    right.push((id.to_string(), *b_start, *b_stop));
+                } else {
+                    removed.push((id.to_string(), *b_start, *b_stop));
+                }
+                continue
+            }
+            if lo < start {
+                left.push((id.to_string(), *b_start, b_start + hi.min(start) - lo));
+            }
+            if hi.min(stop) > lo.max(start) {
+                removed.push((id.to_string(), b_start + lo.max(start) - lo, b_start + hi.min(stop) - lo));
+            }
+            if stop < hi {
+                right.push((id.to_string(), b_start + lo.max(stop) - lo, *b_stop));
+            }
+        }
+        // Merge the blocks on either side of the cut
+        if let (Some(last), Some(first)) = (left.last_mut(), right.first()) {
+            if last.0 == first.0 && last.2 == first.1 {
+                last.2 = first.2;
+                right.remove(0);
+            }
+        }
+        left.append(&mut right);
+        self.coords = left;
+        Ok(BlockSpace{ coords: removed })
+    }
+
+    #[args(gap_id = "\"g\"")]
+    /// insert_gap(pos, length, gap_id="g", /)
+    /// --
+    /// 
+    /// Inserts a gap block of the given length at the relative position
+    /// pos, splitting the block at that position if needed. Blocks after
+    /// the gap are shifted by length.
+    fn insert_gap(&mut self, pos: i32, length: i32, gap_id: &str) -> PyResult<()> {
+        if pos < 0 || pos > self.len()? {
+            return Err(exceptions::IndexError::py_err(
+                format!("position out of range: {}", pos)))
+        }
+        if length <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("length must be greater than 0: {}", length)))
+        }
+        let mut coords: Vec<(String, i32, i32)> = Vec::with_capacity(self.coords.len() + 2);
+        let mut offset = 0;
+        let mut inserted = false;
+        for (id, b_start, b_stop) in self.coords.iter() {
+            if inserted {
+                coords.push((id.to_string(), b_start + length, b_stop + length));
+                continue
+            }
+            if pos < offset + b_stop - b_start {
+                let split = b_start + pos - offset;
+                if split > *b_start {
+                    coords.push((id.to_string(), *b_start, split));
+                }
+                coords.push((gap_id.to_string(), split, split + length));
+                coords.push((id.to_string(), split + length, b_stop + length));
+                inserted = true;
+            } else {
+                coords.push((id.to_string(), *b_start, *b_stop));
+            }
+            offset += b_stop - b_start;
+        }
+        if !inserted {
+            let start = self.coords.last().map(|(_, _, stop)| *stop).unwrap_or(0);
+            coords.push((gap_id.to_string(), start, start + length));
+        }
+        self.coords = coords;
+        Ok(())
+    }
+
+    /// remove_stable(positions, payload, /)
+    /// --
+    /// 
+    /// Removes points based on a list of relative positions, where
+    /// payload is a list of objects attached to each position.
+    /// Returns the payload sorted by position. Payload objects of equal
+    /// positions keep their original order.
+    fn remove_stable(&mut self, positions: Vec<i32>, payload: Vec<PyObject>) -> PyResult<Vec<PyObject>> {
+        if positions.len() != payload.len() {
+            return Err(exceptions::ValueError::py_err(
+                "lengths of positions and payload do not match"))
+        }
+        let mut pairs: Vec<(i32, PyObject)> = positions.iter().cloned()
+            .zip(payload)
+            .collect();
+        pairs.sort_by_key(|(i, _)| *i);
+        self.remove(positions)?;
+        Ok(pairs.into_iter().map(|(_, x)| x).collect())
+    }
+
     /// remove_blocks(ids, /)
     /// --
     /// 
@@ -250,7 +1067,9 @@ impl BlockSpace {
     /// retain(positions, /)
     /// 
     /// Retains points in linear space specified by a
-    /// list of positions to keep.
+    /// list of positions to keep. Positions are processed in ascending
+    /// order using a stable sort, so repeated positions keep the order
+    /// in which they were given.
     fn retain(&mut self, positions: Vec<i32>) -> PyResult<()> {
         // Check if positions list is empty or not using max()
         if let Some(max) = positions.iter().max() {
@@ -267,7 +1086,7 @@ impl BlockSpace {
             let mut ext_coord_list: Vec<i32> = Vec::with_capacity(positions.len());
             let mut ext_id_list: Vec<String> = Vec::with_capacity(positions.len());
             let mut positions = positions;
-            positions.sort_unstable();
+            positions.sort();
             for i in positions.iter() {
                 let i = *i as usize;
                 ext_coord_list.push(coord_list[i]);
@@ -363,6 +1182,52 @@ impl BlockSpace {
     //                                         dimension: block list is empty"))
     // }
 
+    /// label_ranges(ranges, /)
+    /// --
+    /// 
+    /// Relabels positions using a list of (lo, hi, id) absolute ranges,
+    /// splitting blocks at the range boundaries. Ranges are half-open
+    /// and are applied in order, so later ranges win where they overlap.
+    /// Contiguous pieces relabeled by the same range are merged, while
+    /// blocks outside the range are left unchanged.
+    fn label_ranges(&mut self, ranges: Vec<(i32, i32, String)>) -> PyResult<()> {
+        for (lo, hi, _) in ranges.iter() {
+            if lo > hi {
+                return Err(exceptions::ValueError::py_err(
+                    format!("start must be less than stop: {} !< {}", lo, hi)))
+            }
+        }
+        for (lo, hi, new_id) in ranges.iter() {
+            let mut coords: Vec<(String, i32, i32)> = Vec::with_capacity(self.coords.len());
+            // Whether the last pushed block was relabeled by this range
+            let mut last_painted = false;
+            for (id, start, stop) in self.coords.iter() {
+                if stop <= lo || start >= hi {
+                    coords.push((id.to_string(), *start, *stop));
+                    last_painted = false;
+                    continue
+                }
+                if start < lo {
+                    coords.push((id.to_string(), *start, *lo));
+                    last_painted = false;
+                }
+                let piece_start = if start > lo { *start } else { *lo };
+                let piece_stop = if stop < hi { *stop } else { *hi };
+                match coords.last_mut() {
+                    Some(last) if last_painted && last.2 == piece_start => last.2 = piece_stop,
+                    _ => coords.push((new_id.to_string(), piece_start, piece_stop)),
+                }
+                last_painted = true;
+                if stop > hi {
+                    coords.push((id.to_string(), *hi, *stop));
+                    last_painted = false;
+                }
+            }
+            self.coords = coords;
+        }
+        Ok(())
+    }
+
     // TODO: Add insert and append methods
 
     // start, stop, full_len
@@ -392,6 +1257,107 @@ impl BlockSpace {
         }
     }
 
+    /// bounds()
+    /// --
+    /// 
+    /// Returns the lower and upper bounds of the linear space
+    /// as a (lb, ub) tuple.
+    fn bounds(&self) -> PyResult<(i32, i32)> {
+        match (self.coords.first(), self.coords.last()) {
+            (Some((_, lb, _)), Some((_, _, ub))) => Ok((*lb, *ub)),
+            _ => Err(exceptions::ValueError::py_err(
+                "linear space is empty"))
+        }
+    }
+
+    /// tighten()
+    /// --
+    /// 
+    /// Removes empty blocks from the start and end of the linear space
+    /// so that lb() and ub() reflect the first and last covered blocks.
+    /// The coordinates of the remaining blocks are not changed.
+    fn tighten(&mut self) -> PyResult<()> {
+        while let Some((_, start, stop)) = self.coords.last() {
+            if start < stop {
+                break
+            }
+            self.coords.pop();
+        }
+        let first = self.coords.iter()
+            .position(|(_, start, stop)| start < stop)
+            .unwrap_or(self.coords.len());
+        self.coords.drain(..first);
+        Ok(())
+    }
+
+    /// scale(factor, /)
+    /// --
+    /// 
+    /// Multiplies the start and stop of every block by factor in place,
+    /// keeping the ids. For example, a factor of 3 converts codon
+    /// coordinates to nucleotide coordinates. Raises OverflowError if a
+    /// scaled coordinate does not fit in a 32-bit integer, leaving the
+    /// space unchanged.
+    fn scale(&mut self, factor: i32) -> PyResult<()> {
+        if factor <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("factor must be greater than 0: {}", factor)))
+        }
+        let mut coords: Vec<(String, i32, i32)> = Vec::with_capacity(self.coords.len());
+        for (id, start, stop) in self.coords.iter() {
+            match (start.checked_mul(factor), stop.checked_mul(factor)) {
+                (Some(start), Some(stop)) => coords.push((id.to_string(), start, stop)),
+                _ => return Err(exceptions::OverflowError::py_err(
+                    format!("scaled coordinates are out of range: factor {}", factor))),
+            }
+        }
+        self.coords = coords;
+        Ok(())
+    }
+
+    /// scaled(factor, /)
+    /// --
+    /// 
+    /// Returns a copy of the linear space scaled by factor.
+    fn scaled(&self, factor: i32) -> PyResult<BlockSpace> {
+        let mut space = self.clone();
+        space.scale(factor)?;
+        Ok(space)
+    }
+
+    /// simplify(max_blocks, /)
+    /// --
+    /// 
+    /// Merges adjacent blocks until the linear space has at most
+    /// max_blocks blocks. At each step, the adjacent pair with the
+    /// smallest distortion is merged into a single block spanning both.
+    /// The distortion is the number of positions added to fill the
+    /// space between the pair, plus the length of the shorter block if
+    /// the ids differ. The merged block keeps the id of the longer block.
+    /// Coverage can only grow.
+    fn simplify(&mut self, max_blocks: i32) -> PyResult<()> {
+        if max_blocks <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("max_blocks must be greater than 0: {}", max_blocks)))
+        }
+        while self.coords.len() > max_blocks as usize {
+            let cost = |i: usize| {
+                let (a_id, a_start, a_stop) = &self.coords[i];
+                let (b_id, b_start, b_stop) = &self.coords[i + 1];
+                let (a_len, b_len) = (a_stop - a_start, b_stop - b_start);
+                let span = a_stop.max(b_stop) - a_start.min(b_start);
+                let relabel = if a_id == b_id { 0 } else { a_len.min(b_len) };
+                span - a_len - b_len + relabel
+            };
+            let i = (0..self.coords.len() - 1).min_by_key(|i| cost(*i)).unwrap();
+            let (b_id, b_start, b_stop) = self.coords.remove(i + 1);
+            let (a_id, a_start, a_stop) = self.coords[i].clone();
+            let id = if b_stop - b_start > a_stop - a_start { b_id } else { a_id };
+            self.coords[i] = (id, a_start.min(b_start), a_stop.max(b_stop));
+        }
+        Ok(())
+    }
+
     /// Returns the total length of the linear space.
     fn len(&self) -> PyResult<i32> {
         if self.coords.len() == 0 {
@@ -403,9 +1369,209 @@ impl BlockSpace {
         }
         Ok(length)
     }
-    
+
+    #[args(gap_id = "\"g\"")]
+    /// covered_length(gap_id="g", /)
+    /// --
+    /// 
+    /// Returns the total length of blocks whose id is not gap_id.
+    fn covered_length(&self, gap_id: &str) -> PyResult<i32> {
+        Ok(self.coords.iter()
+            .filter(|(id, _, _)| id != gap_id)
+            .map(|(_, start, stop)| stop - start)
+            .sum())
+    }
+
+    // Validation
+
+    /// validate()
+    /// --
+    /// 
+    /// Checks that blocks are non-empty, sorted by start and
+    /// non-overlapping. Raises ValueError at the first invalid block.
+    fn validate(&self) -> PyResult<()> {
+        let mut prev_stop: Option<i32> = None;
+        for (i, (_, start, stop)) in self.coords.iter().enumerate() {
+            if start >= stop {
+                return Err(exceptions::ValueError::py_err(
+                    format!("block {} is empty: {}:{}", i, start, stop)))
+            }
+            if let Some(p) = prev_stop {
+                if *start < p {
+                    return Err(exceptions::ValueError::py_err(
+                        format!("block {} is unsorted or overlapping: {} < {}",
+                                i, start, p)))
+                }
+            }
+            prev_stop = Some(*stop);
+        }
+        Ok(())
+    }
+
+    /// repair()
+    /// --
+    /// 
+    /// Sorts blocks by start, trims blocks that overlap a preceding block,
+    /// and drops blocks that are left empty.
+    /// Returns the number of blocks that were moved, trimmed or dropped.
+    fn repair(&mut self) -> PyResult<i32> {
+        let mut corrections = 0;
+        // Stable sort so that blocks with equal starts keep their order
+        let mut order: Vec<usize> = (0..self.coords.len()).collect();
+        order.sort_by_key(|i| self.coords[*i].1);
+        corrections += order.iter().enumerate().filter(|(j, i)| j != *i).count() as i32;
+
+        let mut coords: Vec<(String, i32, i32)> = Vec::with_capacity(self.coords.len());
+        let mut prev_stop: Option<i32> = None;
+        for i in order {
+            let (id, start, stop) = &self.coords[i];
+            let mut start = *start;
+            let mut trimmed = false;
+            if let Some(p) = prev_stop {
+                if start < p {
+                    start = p;
+                    trimmed = true;
+                }
+            }
+            if start >= *stop {
+                // Drop empty blocks
+                corrections += 1;
+                continue
+            }
+            if trimmed {
+                corrections += 1;
+            }
+            coords.push((id.to_string(), start, *stop));
+            prev_stop = Some(*stop);
+        }
+        self.coords = coords;
+        Ok(corrections)
+    }
+
+    /// gaps_between()
+    /// --
+    /// 
+    /// Returns the regions between the lower and upper bounds that
+    /// are not covered by any block, as a list of "g" blocks.
+    fn gaps_between(&self) -> PyResult<Vec<Block>> {
+        let mut cursor = GapCursor::new(self);
+        let mut gaps: Vec<Block> = Vec::new();
+        while let Some(gap) = cursor.next_gap() {
+            gaps.push(gap);
+        }
+        Ok(gaps)
+    }
+
+    /// iter_gaps()
+    /// --
+    /// 
+    /// Returns an iterator over the regions that are not covered by
+    /// any block. Yields the same blocks as gaps_between().
+    fn iter_gaps(&self) -> PyResult<Py<GapIterator>> {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        Py::new(py, |token| GapIterator{ cursor: GapCursor::new(self), token })
+    }
+
+    /// boundary_points()
+    /// --
+    /// 
+    /// Returns a list of (coordinate, delta) pairs sorted by coordinate,
+    /// where delta is 1 at the start of each block and -1 at its stop.
+    /// The cumulative sum of deltas gives the coverage.
+    fn boundary_points(&self) -> PyResult<Vec<(i32, i32)>> {
+        let mut points: Vec<(i32, i32)> = self.coords.iter()
+            .flat_map(|(_, start, stop)| vec![(*start, 1), (*stop, -1)])
+            .collect();
+        points.sort_unstable();
+        Ok(points)
+    }
+
+    /// overlap_counts(queries, /)
+    /// --
+    /// 
+    /// Returns, for each query block, the number of positions in the
+    /// linear space that fall within the range of the query.
+    fn overlap_counts(&self, queries: Vec<&Block>) -> PyResult<Vec<i32>> {
+        Ok(queries.iter()
+            .map(|query| self.overlap_length(query.start, query.stop))
+            .collect())
+    }
+
+    /// intersection_length(block, /)
+    /// --
+    /// 
+    /// Returns the number of positions in the linear space that fall
+    /// within the range of the given block.
+    fn intersection_length(&self, block: &Block) -> PyResult<i32> {
+        Ok(self.overlap_length(block.start, block.stop))
+    }
+
+    /// to_bitmap(lo, hi, /)
+    /// --
+    /// 
+    /// Returns a list of booleans of length hi - lo where each item is
+    /// True if the absolute coordinate lo + j is covered by a block.
+    fn to_bitmap(&self, lo: i32, hi: i32) -> PyResult<Vec<bool>> {
+        if lo >= hi {
+            return Err(exceptions::ValueError::py_err(
+                format!("lo must be less than hi: {} !< {}", lo, hi)))
+        }
+        let mut bitmap = vec![false; (hi - lo) as usize];
+        for (_, start, stop) in self.coords.iter() {
+            for x in *start.max(&lo)..*stop.min(&hi) {
+                bitmap[(x - lo) as usize] = true;
+            }
+        }
+        Ok(bitmap)
+    }
+
     // Format conversion
 
+    #[staticmethod]
+    /// from_extended_str(s, /)
+    /// --
+    /// 
+    /// Returns a new BlockSpace parsed from an extended string
+    /// representation of semicolon-separated id=start:stop blocks.
+    /// Blocks must be sorted and must not overlap.
+    fn from_extended_str(s: &str) -> PyResult<BlockSpace> {
+        let mut coords: Vec<(String, i32, i32)> = Vec::new();
+        if s.len() == 0 {
+            return Ok(BlockSpace{ coords })
+        }
+        for segment in s.split(';') {
+            let cap = match EXTENDED_BLOCK_REGEX.captures(segment) {
+                Some(cap) => cap,
+                None => return Err(exceptions::ValueError::py_err(
+                    format!("malformed block: {}", segment)))
+            };
+            let start = match cap[2].parse::<i32>() {
+                Ok(v) => v,
+                Err(_) => return Err(exceptions::ValueError::py_err(
+                    "error converting block start to i32"))
+            };
+            let stop = match cap[3].parse::<i32>() {
+                Ok(v) => v,
+                Err(_) => return Err(exceptions::ValueError::py_err(
+                    "error converting block stop to i32"))
+            };
+            if start > stop {
+                return Err(exceptions::ValueError::py_err(
+                    format!("start must be less than stop: {} !< {}",
+                            start, stop)))
+            }
+            if let Some((_, _, last_stop)) = coords.last() {
+                if start < *last_stop {
+                    return Err(exceptions::ValueError::py_err(
+                        format!("block is unsorted or overlapping: {}", segment)))
+                }
+            }
+            coords.push((cap[1].to_string(), start, stop));
+        }
+        Ok(BlockSpace{ coords })
+    }
+
     /// to_blocks()
     /// --
     /// 
@@ -425,19 +1591,26 @@ impl BlockSpace {
     // fn to_points(&self) -> PyResult<Vec<Point>> {
     // }
 
-    /// to_list()
+    #[args(expand = "false")]
+    /// to_list(expand=False, /)
     /// --
     /// 
     /// Returns the linear space as a list of start, stop, and id tuples.
-    fn to_list(&self) -> PyResult<Vec<(String, i32, i32)>> {
-        if self.coords.len() == 0 {
-            return Ok(Vec::new())
+    /// If expand is True, returns one (coordinate, index, id) tuple per
+    /// position instead, where index is the relative position. Note that
+    /// the expanded list grows with the length of the space rather than
+    /// the number of blocks, and can use a lot of memory for large spaces.
+    fn to_list(&self, expand: bool) -> PyResult<PyObject> {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        if expand {
+            return Ok(self.expanded_list().into_object(py))
         }
         let mut list: Vec<(String, i32, i32)> = Vec::new();
         for (id, start, stop) in self.coords.iter() {
             list.push((id.to_string(), *start, *stop));
         }
-        Ok(list)
+        Ok(list.into_object(py))
     }
 
     /// to_arrays()
@@ -546,13 +1719,127 @@ impl BlockSpace {
         Ok(strings.join(","))  
     }
 
-    /// copy()
-    /// --
-    /// 
-    /// Returns a deep copy of the current linear space.
-    fn copy(&self) -> PyResult<BlockSpace> {
-        let coords = self.coords.clone();
-        Ok(BlockSpace{ coords })
+    /// to_compressed_str_v2()
+    /// --
+    /// 
+    /// Converts blocks into a compressed string representation
+    /// prefixed by the lower bound, e.g. "10|s=5;g=3".
+    /// Assumes that blocks are contiguous so that absolute positions
+    /// can be recovered from the lower bound and block lengths.
+    /// Raises ValueError if an id contains "=", ";" or "|".
+    fn to_compressed_str_v2(&self) -> PyResult<String> {
+        if self.coords.len() == 0 {
+            return Ok(String::new())
+        }
+        let mut strings: Vec<String> = Vec::new();
+        let mut last_stop = self.coords[0].1;
+        for (id, start, stop) in self.coords.iter() {
+            if *start != last_stop {
+                return Err(exceptions::ValueError::py_err(
+                    format!("cannot represent a linear space with \
+                             non-contiguous blocks: {} != {}", start, last_stop)))
+            }
+            if id.contains(['=', ';', '|']) {
+                return Err(exceptions::ValueError::py_err(
+                    format!("cannot represent a block id containing \
+                             '=', ';' or '|': {}", id)))
+            }
+            strings.push(format!("{}={}", id, stop - start));
+            last_stop = *stop;
+        }
+        Ok(format!("{}|{}", self.coords[0].1, strings.join(";")))
+    }
+
+    /// to_dot()
+    /// --
+    /// 
+    /// Returns the block structure as a Graphviz DOT graph.
+    /// Each block is a node labeled id:start-stop and edges connect
+    /// consecutive blocks. Gap blocks are colored differently.
+    /// Quotes and backslashes in ids are escaped.
+    fn to_dot(&self) -> PyResult<String> {
+        let mut lines: Vec<String> = vec!["digraph BlockSpace {".to_string()];
+        for (i, (id, start, stop)) in self.coords.iter().enumerate() {
+            let color = if id == "g" { "gray" } else { "black" };
+            let label = id.replace('\\', "\\\\").replace('"', "\\\"");
+            lines.push(format!("    b{} [label=\"{}:{}-{}\", color={}];",
+                               i, label, start, stop, color));
+        }
+        for i in 1..self.coords.len() {
+            lines.push(format!("    b{} -> b{};", i - 1, i));
+        }
+        lines.push("}".to_string());
+        Ok(lines.join("\n"))
+    }
+
+    /// copy()
+    /// --
+    /// 
+    /// Returns a deep copy of the current linear space.
+    fn copy(&self) -> PyResult<BlockSpace> {
+        let coords = self.coords.clone();
+        Ok(BlockSpace{ coords })
+    }
+}
+
+/// Walks the regions that are not covered by the blocks of a
+/// BlockSpace, in ascending order.
+struct GapCursor {
+    // (start, stop) of each block sorted by start
+    blocks: Vec<(i32, i32)>,
+    index: usize,
+    last_stop: i32,
+}
+
+impl GapCursor {
+    fn new(space: &BlockSpace) -> GapCursor {
+        let mut blocks: Vec<(i32, i32)> = space.coords.iter()
+            .map(|(_, start, stop)| (*start, *stop))
+            .collect();
+        blocks.sort_unstable();
+        let last_stop = match blocks.first() {
+            Some((start, _)) => *start,
+            None => 0,
+        };
+        GapCursor { blocks, index: 0, last_stop }
+    }
+
+    /// Returns the next uncovered region, or None if there are
+    /// no more gaps.
+    fn next_gap(&mut self) -> Option<Block> {
+        while self.index < self.blocks.len() {
+            let (start, stop) = self.blocks[self.index];
+            self.index += 1;
+            let gap_start = self.last_stop;
+            if stop > self.last_stop {
+                self.last_stop = stop;
+            }
+            if start > gap_start {
+                return Some(Block{ id: "g".to_string(), start: gap_start, stop: start })
+            }
+        }
+        None
+    }
+}
+
+#[pyclass]
+/// Iterator over the uncovered regions of a BlockSpace.
+pub struct GapIterator {
+
+    cursor: GapCursor,
+
+    token: PyToken,
+
+}
+
+#[pyproto]
+impl PyIterProtocol for GapIterator {
+    fn __iter__(&mut self) -> PyResult<PyObject> {
+        Ok(self.into())
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Block>> {
+        Ok(self.cursor.next_gap())
     }
 }
 
@@ -632,6 +1919,7 @@ pub fn arrays_to_linspace(coords: Vec<i32>, ids: Vec<String>) -> PyResult<BlockS
 lazy_static! {
     static ref BLOCK_REGEX: Regex = Regex::new(r"([A-Za-z0-9]+?)\x3D(\x2D*\d+)\3A(\x2D*\d+)").unwrap();
     static ref SIMPLE_BLOCK_REGEX: Regex = Regex::new(r"(\x2D*\d+)\x3A(\x2D*\d+)").unwrap();
+    static ref EXTENDED_BLOCK_REGEX: Regex = Regex::new(r"^([^=;]+)=(-?\d+):(-?\d+)$").unwrap();
 }
 
 #[pyfunction]
@@ -673,6 +1961,42 @@ pub fn simple_block_str_to_linspace(blocks_str: &str) -> PyResult<BlockSpace> {
     Ok(BlockSpace{ coords })
 }
 
+#[pyfunction]
+/// compressed_str_v2_to_linspace(compressed_str, /)
+/// --
+///
+/// Returns a linear space created from a string produced by
+/// BlockSpace.to_compressed_str_v2.
+pub fn compressed_str_v2_to_linspace(compressed_str: &str) -> PyResult<BlockSpace> {
+    let mut coords: Vec<(String, i32, i32)> = Vec::new();
+    if compressed_str.len() == 0 {
+        return Ok(BlockSpace{ coords })
+    }
+    let mut parts = compressed_str.splitn(2, '|');
+    let mut start = match parts.next().unwrap().parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => return Err(exceptions::ValueError::py_err(
+            "error converting lower bound to i32"))
+    };
+    let blocks_str = match parts.next() {
+        Some(x) => x,
+        None => return Err(exceptions::ValueError::py_err(
+            "missing lower bound delimiter: |"))
+    };
+    for block_str in blocks_str.split(';') {
+        let mut fields = block_str.splitn(2, '=');
+        let id = fields.next().unwrap();
+        let length = match fields.next().map(|x| x.parse::<i32>()) {
+            Some(Ok(v)) if v >= 0 => v,
+            _ => return Err(exceptions::ValueError::py_err(
+                format!("invalid block: {}", block_str)))
+        };
+        coords.push((id.to_string(), start, start + length));
+        start += length;
+    }
+    Ok(BlockSpace{ coords })
+}
+
 #[pyclass(subclass)]
 #[derive(Clone)]
 /// CoordSpace(init_state, start, stop)
@@ -683,7 +2007,22 @@ pub struct CoordSpace {
 
     // start: i32,
     // stop: i32,
-    coords: Vec<i32>
+    coords: Vec<i32>,
+
+    // Original ids of each position, parallel to coords.
+    // Empty if the space was not created with labels.
+    labels: Vec<Option<String>>,
+
+    // Blocks computed by coalesce. Cleared by every method
+    // that modifies coords or labels.
+    block_cache: Option<Vec<Block>>,
+
+    // Prefix sums of sequence positions computed by
+    // build_coverage_index. Cleared like block_cache.
+    coverage_index: Option<Vec<i32>>,
+
+    // Set by freeze. A frozen space can be hashed.
+    frozen: bool,
 
 }
 
@@ -699,9 +2038,7 @@ impl CoordSpace {
                         start, stop)))
         }
         obj.init(|_| {
-            CoordSpace { 
-                coords: (start..stop).collect(),
-            }
+            CoordSpace::new((start..stop).collect())
         })
     }
 
@@ -714,24 +2051,363 @@ impl CoordSpace {
                 return Err(exceptions::IndexError::py_err(format!("index out of range: {}", max)))
             }
             let mut new_coords: Vec<i32> = Vec::new();
+            let mut new_labels: Vec<Option<String>> = Vec::new();
             for i in coords.iter() {
                 new_coords.push(self.coords[*i as usize]);
+                if self.labels.len() > 0 {
+                    new_labels.push(self.labels[*i as usize].clone());
+                }
             }
-            Ok(CoordSpace{ coords: new_coords })
+            Ok(CoordSpace::with_labels(new_coords, new_labels))
         } else {
-            Ok(CoordSpace { coords: self.coords.clone()})
+            Ok(CoordSpace::with_labels(self.coords.clone(), self.labels.clone()))
+        }
+    }
+
+    /// extract_ranges(ranges)
+    /// 
+    /// Extracts coordinates by a list of (start, stop) relative ranges
+    /// as a new CoordSpace. Ranges are half-open and are concatenated in
+    /// the given order.
+    fn extract_ranges(&self, ranges: Vec<(i32, i32)>) -> PyResult<CoordSpace> {
+        let length = self.coords.len() as i32;
+        for (start, stop) in ranges.iter() {
+            if start > stop {
+                return Err(exceptions::ValueError::py_err(
+                    format!("start must be less than stop: {} !< {}", start, stop)))
+            }
+            if *start < 0 || *stop > length {
+                return Err(exceptions::IndexError::py_err(
+                    format!("range out of range: {}:{}", start, stop)))
+            }
+        }
+        let mut sorted_ranges = ranges.clone();
+        sorted_ranges.sort_unstable();
+        for i in 1..sorted_ranges.len() {
+            if sorted_ranges[i].0 < sorted_ranges[i-1].1 {
+                return Err(exceptions::ValueError::py_err(
+                    format!("ranges overlap: {}:{} and {}:{}",
+                            sorted_ranges[i-1].0, sorted_ranges[i-1].1,
+                            sorted_ranges[i].0, sorted_ranges[i].1)))
+            }
+        }
+        let positions: Vec<i32> = ranges.iter()
+            .flat_map(|(start, stop)| *start..*stop)
+            .collect();
+        if positions.len() == 0 {
+            return Ok(CoordSpace::new(Vec::new()))
+        }
+        self.extract(positions)
+    }
+
+    /// resample(target_length)
+    /// 
+    /// Returns a new CoordSpace of the given length where each position
+    /// is mapped proportionally to the nearest position in this space.
+    /// The first and last positions always map to the first and last
+    /// positions of this space.
+    fn resample(&self, target_length: i32) -> PyResult<CoordSpace> {
+        if target_length <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("target length must be greater than 0: {}", target_length)))
+        }
+        if self.coords.len() == 0 {
+            return Err(exceptions::ValueError::py_err("linear space is empty"))
+        }
+        let last = (self.coords.len() - 1) as f64;
+        let positions: Vec<i32> = (0..target_length).map(|j| {
+            if target_length == 1 {
+                0
+            } else {
+                (j as f64 * last / (target_length - 1) as f64).round() as i32
+            }
+        }).collect();
+        self.extract(positions)
+    }
+
+    /// sample(k, seed)
+    /// 
+    /// Returns a new linear space of k positions chosen at random,
+    /// without replacement, in the order they were drawn. The same seed
+    /// always gives the same sample.
+    fn sample(&self, k: i32, seed: u64) -> PyResult<CoordSpace> {
+        if k < 0 || k > self.coords.len() as i32 {
+            return Err(exceptions::ValueError::py_err(
+                format!("k must be between 0 and the length of the space: {}", k)))
+        }
+        // Partial Fisher-Yates shuffle of the relative positions
+        let mut positions: Vec<i32> = (0..self.coords.len() as i32).collect();
+        let mut state = seed;
+        for i in 0..k as usize {
+            let j = i + (splitmix64(&mut state) % (positions.len() - i) as u64) as usize;
+            positions.swap(i, j);
+        }
+        positions.truncate(k as usize);
+        if positions.len() == 0 {
+            return Ok(CoordSpace::new(Vec::new()))
+        }
+        self.extract(positions)
+    }
+
+    /// align_to(reference)
+    /// 
+    /// Inserts gaps so that the linear space follows the gap pattern of
+    /// the reference. Sequence coordinates fill the non-gap columns of
+    /// the reference in order, and any remaining non-gap columns are
+    /// filled with gaps. Existing gaps in the linear space are discarded.
+    fn align_to(&mut self, reference: &CoordSpace) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        let seq_positions: Vec<usize> = (0..self.coords.len())
+            .filter(|i| self.coords[*i] != -1)
+            .collect();
+        let ref_seq_len = reference.coords.iter().filter(|x| **x != -1).count();
+        if seq_positions.len() > ref_seq_len {
+            return Err(exceptions::ValueError::py_err(
+                format!("sequence length is greater than the number of \
+                         non-gap columns in the reference: {} > {}",
+                        seq_positions.len(), ref_seq_len)))
+        }
+        let mut coords: Vec<i32> = Vec::with_capacity(reference.coords.len());
+        let mut labels: Vec<Option<String>> = Vec::new();
+        let mut seq_iter = seq_positions.iter();
+        for ref_coord in reference.coords.iter() {
+            let next = if *ref_coord == -1 { None } else { seq_iter.next() };
+            match next {
+                Some(i) => {
+                    coords.push(self.coords[*i]);
+                    if self.labels.len() > 0 {
+                        labels.push(self.labels[*i].clone());
+                    }
+                },
+                None => {
+                    coords.push(-1);
+                    if self.labels.len() > 0 {
+                        labels.push(None);
+                    }
+                }
+            }
+        }
+        self.coords = coords;
+        self.labels = labels;
+        Ok(())
+    }
+
+    #[staticmethod]
+    /// align_starts(a, b)
+    /// 
+    /// Returns copies of both linear spaces where the space that starts
+    /// at a higher sequence coordinate is padded at the start with one
+    /// gap per coordinate of difference. This lines up the first
+    /// coordinate shared by both spaces, e.g. (2..5) and (0..3) become
+    /// [-1, -1, 2, 3, 4] and [0, 1, 2]. Raises ValueError if either space
+    /// has no sequence coordinates.
+    fn align_starts(a: &CoordSpace, b: &CoordSpace) -> PyResult<(CoordSpace, CoordSpace)> {
+        let (a_start, _) = a.sequence_bounds()?;
+        let (b_start, _) = b.sequence_bounds()?;
+        let mut a = CoordSpace::with_labels(a.coords.clone(), a.labels.clone());
+        let mut b = CoordSpace::with_labels(b.coords.clone(), b.labels.clone());
+        let padding = CoordSpace::new(vec![-1; (a_start - b_start).unsigned_abs() as usize]);
+        if a_start > b_start {
+            a.paste(0, &padding)?;
+        } else if b_start > a_start {
+            b.paste(0, &padding)?;
+        }
+        Ok((a, b))
+    }
+
+    /// approx_equals(other, tol)
+    /// 
+    /// Returns True if both spaces have the same length and gap pattern,
+    /// and their sequence coordinates each differ by at most tol.
+    /// Otherwise, returns False.
+    fn approx_equals(&self, other: &CoordSpace, tol: i32) -> PyResult<bool> {
+        if self.coords.len() != other.coords.len() {
+            return Ok(false)
+        }
+        Ok(self.coords.iter().zip(other.coords.iter()).all(|(a, b)| {
+            if *a == -1 || *b == -1 {
+                a == b
+            } else {
+                (a - b).abs() <= tol
+            }
+        }))
+    }
+
+    /// common_prefix_length(other)
+    /// 
+    /// Returns the number of leading positions that have the same
+    /// coordinate, or are both gaps, in both linear spaces.
+    fn common_prefix_length(&self, other: &CoordSpace) -> PyResult<i32> {
+        Ok(self.coords.iter().zip(other.coords.iter())
+            .take_while(|(a, b)| a == b)
+            .count() as i32)
+    }
+
+    /// common_suffix_length(other)
+    /// 
+    /// Returns the number of trailing positions that have the same
+    /// coordinate, or are both gaps, in both linear spaces.
+    fn common_suffix_length(&self, other: &CoordSpace) -> PyResult<i32> {
+        Ok(self.coords.iter().rev().zip(other.coords.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count() as i32)
+    }
+
+    /// interleave(other)
+    /// 
+    /// Returns a new CoordSpace alternating the positions of this space
+    /// and the other space. Both spaces must have the same length.
+    fn interleave(&self, other: &CoordSpace) -> PyResult<CoordSpace> {
+        if self.coords.len() != other.coords.len() {
+            return Err(exceptions::ValueError::py_err(
+                format!("lengths do not match: {} != {}",
+                        self.coords.len(), other.coords.len())))
+        }
+        let mut coords: Vec<i32> = Vec::with_capacity(self.coords.len() * 2);
+        let mut labels: Vec<Option<String>> = Vec::new();
+        let labeled = self.labels.len() > 0 || other.labels.len() > 0;
+        for i in 0..self.coords.len() {
+            coords.push(self.coords[i]);
+            coords.push(other.coords[i]);
+            if labeled {
+                labels.push(Some(self.id_at(i)?));
+                labels.push(Some(other.id_at(i)?));
+            }
+        }
+        Ok(CoordSpace::with_labels(coords, labels))
+    }
+
+    /// sort_blocks()
+    /// 
+    /// Reorders runs of consecutive coordinates in place so that blocks
+    /// are sorted by their first coordinate. The coordinates within each
+    /// run are kept. Gap runs move together with the sequence run that
+    /// precedes them, and leading gaps stay at the start.
+    fn sort_blocks(&mut self) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        // (anchor, start, stop) of each run
+        let mut segments: Vec<(i32, usize, usize)> = Vec::new();
+        for i in 0..self.coords.len() {
+            let x = self.coords[i];
+            let new_segment = i == 0 ||
+                (x >= 0 && (self.coords[i-1] == -1 || x != self.coords[i-1] + 1));
+            if new_segment {
+                segments.push((x, i, i + 1));
+            } else if let Some(last) = segments.last_mut() {
+                last.2 = i + 1;
+            }
+        }
+        segments.sort_by_key(|(anchor, _, _)| *anchor);
+        let order: Vec<usize> = segments.iter().flat_map(|(_, start, stop)| *start..*stop).collect();
+        self.coords = order.iter().map(|i| self.coords[*i]).collect();
+        if self.labels.len() > 0 {
+            self.labels = order.iter().map(|i| self.labels[*i].clone()).collect();
+        }
+        Ok(())
+    }
+
+    /// tile(width)
+    /// 
+    /// Splits the linear space into consecutive, non-overlapping
+    /// CoordSpaces of width positions. The last tile may be shorter.
+    fn tile(&self, width: i32) -> PyResult<Vec<CoordSpace>> {
+        if width <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("width must be greater than 0: {}", width)))
+        }
+        let width = width as usize;
+        Ok((0..self.coords.len()).step_by(width)
+            .map(|start| {
+                let stop = if start + width < self.coords.len() { start + width } else { self.coords.len() };
+                self.slice(start, stop)
+            })
+            .collect())
+    }
+
+    #[args(drop_last = "false")]
+    /// sliding(window, step, drop_last=False)
+    /// 
+    /// Returns an iterator over windows of the given length, advancing
+    /// by step positions. The last window may be shorter than window
+    /// unless drop_last is True.
+    fn sliding(&self, window: i32, step: i32, drop_last: bool) -> PyResult<Py<CoordWindowIterator>> {
+        if window <= 0 || step <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("window and step must be greater than 0: {}, {}", window, step)))
+        }
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        Py::new(py, |token| CoordWindowIterator{
+            space: self.clone(),
+            window: window as usize,
+            step: step as usize,
+            drop_last,
+            pos: 0,
+            done: false,
+            token,
+        })
+    }
+
+    /// reverse_with_ids(id_swap)
+    /// 
+    /// Reverses the order of positions in place. If id_swap is given,
+    /// the id of each position is also replaced using the mapping.
+    /// Ids that are not in the mapping are kept.
+    fn reverse_with_ids(&mut self, id_swap: Option<HashMap<String, String>>) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        self.coords.reverse();
+        self.labels.reverse();
+        if let Some(id_swap) = id_swap {
+            let mut labels: Vec<Option<String>> = Vec::with_capacity(self.coords.len());
+            for i in 0..self.coords.len() {
+                let id = self.id_at(i)?;
+                match id_swap.get(&id) {
+                    Some(new_id) => labels.push(Some(new_id.to_string())),
+                    None => labels.push(Some(id)),
+                }
+            }
+            self.labels = labels;
+        }
+        Ok(())
+    }
+
+    /// rotate(n)
+    /// 
+    /// Cyclically rotates positions in place by n, where a positive n
+    /// rotates to the left. n is taken modulo the length of the space.
+    /// This models moving the origin of a circular molecule.
+    fn rotate(&mut self, n: i32) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        if self.coords.len() == 0 {
+            return Ok(())
+        }
+        let shift = n.rem_euclid(self.coords.len() as i32) as usize;
+        self.coords.rotate_left(shift);
+        if self.labels.len() > 0 {
+            self.labels.rotate_left(shift);
         }
+        Ok(())
     }
 
     /// remove(coordinates)
     /// 
     /// Removes points in linear space given based on a list of relative
-    /// coordinates.
+    /// coordinates. Runs in O(n) time using a set of the coordinates.
     fn remove(&mut self, coords: Vec<i32>) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
         if let Some(max) = coords.iter().max() {
             if *max >= self.coords.len() as i32 {
                 return Err(exceptions::IndexError::py_err(format!("index out of range: {}", max)))
             }
+            let coords: HashSet<i32> = coords.into_iter().collect();
+            if self.labels.len() > 0 {
+                self.labels = self.labels.iter().enumerate().filter(|(i, _)| !coords.contains(&(*i as i32))).map(|(_, x)| x.clone() ).collect();
+            }
             self.coords = self.coords.iter().enumerate().filter(|(i, _)| !coords.contains(&(*i as i32))).map(|(_, x)| *x ).collect();
             Ok(())
         } else {
@@ -740,23 +2416,229 @@ impl CoordSpace {
         
     }
 
+    /// remove_gaps_recording()
+    /// 
+    /// Removes all gaps from the linear space. Returns a list of
+    /// (index, length) pairs, where index is the relative position
+    /// in the ungapped space where a gap run had been and length is
+    /// the length of that gap run.
+    fn remove_gaps_recording(&mut self) -> PyResult<Vec<(i32, i32)>> {
+        self.check_mutable()?;
+        self.clear_cache();
+        let mut gaps: Vec<(i32, i32)> = Vec::new();
+        let mut removed = 0;
+        for (is_gap, start, stop) in self.runs() {
+            if is_gap {
+                gaps.push(((start - removed) as i32, (stop - start) as i32));
+                removed += stop - start;
+            }
+        }
+        if self.labels.len() > 0 {
+            let coords = &self.coords;
+            self.labels = self.labels.iter().enumerate()
+                .filter(|(i, _)| coords[*i] != -1)
+                .map(|(_, x)| x.clone())
+                .collect();
+        }
+        self.coords.retain(|x| *x != -1);
+        Ok(gaps)
+    }
+
+    /// insert_gaps(gaps)
+    /// 
+    /// Inserts gap runs given as a list of (index, length) pairs
+    /// returned by remove_gaps_recording. Each index is a relative
+    /// position in the space before any of the gaps are inserted.
+    fn insert_gaps(&mut self, gaps: Vec<(i32, i32)>) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        for (index, length) in gaps.iter() {
+            if *index < 0 || *index > self.coords.len() as i32 {
+                return Err(exceptions::IndexError::py_err(format!("index out of range: {}", index)))
+            }
+            if *length < 0 {
+                return Err(exceptions::ValueError::py_err(
+                    format!("gap length must not be negative: {}", length)))
+            }
+        }
+        // Insert from the end so that earlier indices stay valid
+        let mut gaps = gaps;
+        gaps.sort_by_key(|g| std::cmp::Reverse(g.0));
+        for (index, length) in gaps {
+            let i = index as usize;
+            self.coords.splice(i..i, vec![-1; length as usize]);
+            if self.labels.len() > 0 {
+                self.labels.splice(i..i, vec![None; length as usize]);
+            }
+        }
+        Ok(())
+    }
+
+    /// paste(index, other)
+    /// 
+    /// Inserts the coordinates of another CoordSpace at the given
+    /// relative index. Coordinates are inserted as they are.
+    fn paste(&mut self, index: i32, other: &CoordSpace) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        if index < 0 || index > self.coords.len() as i32 {
+            return Err(exceptions::IndexError::py_err(format!("index out of range: {}", index)))
+        }
+        // Copy other first since it may be the same object as self
+        let payload = other.coords.clone();
+        let other_labels = if other.labels.len() > 0 {
+            other.labels.clone()
+        } else {
+            vec![None; payload.len()]
+        };
+        let labeled = self.labels.len() > 0 || other.labels.len() > 0;
+        let i = index as usize;
+        if labeled {
+            if self.labels.len() == 0 {
+                self.labels = vec![None; self.coords.len()];
+            }
+            self.labels.splice(i..i, other_labels);
+        }
+        self.coords.splice(i..i, payload);
+        Ok(())
+    }
+
+    /// remove_range(start, stop)
+    /// 
+    /// Removes the points in the half-open relative range from start
+    /// to stop.
+    fn remove_range(&mut self, start: i32, stop: i32) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        if start > stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must be less than stop: {} !< {}", start, stop)))
+        }
+        if start < 0 || stop > self.coords.len() as i32 {
+            return Err(exceptions::IndexError::py_err(
+                format!("range out of range: {}:{}", start, stop)))
+        }
+        self.coords.drain(start as usize..stop as usize);
+        if self.labels.len() > 0 {
+            self.labels.drain(start as usize..stop as usize);
+        }
+        Ok(())
+    }
+
     /// retain(coordinates)
     /// 
     /// Retains points in linear space specified by a
-    /// list of coordinates to keep.
+    /// list of coordinates to keep. Runs in O(n) time using a set
+    /// of the coordinates.
     fn retain(&mut self, coords: Vec<i32>) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
         if let Some(max) = coords.iter().max() {
             if *max >= self.coords.len() as i32 {
                 return Err(exceptions::IndexError::py_err(format!("index out of range: {}", max)))
             }
+            let coords: HashSet<i32> = coords.into_iter().collect();
+            if self.labels.len() > 0 {
+                self.labels = self.labels.iter().enumerate().filter(|(i, _)| coords.contains(&(*i as i32))).map(|(_, x)| x.clone() ).collect();
+            }
             self.coords = self.coords.iter().enumerate().filter(|(i, _)| coords.contains(&(*i as i32))).map(|(_, x)| *x ).collect();
             Ok(())
         } else {
             self.coords = Vec::new();
+            self.labels = Vec::new();
             Ok(())
         }        
     }
 
+    /// truncate(length)
+    /// 
+    /// Keeps only the first length positions of the linear space.
+    /// Does nothing if length is larger than the space.
+    fn truncate(&mut self, length: i32) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        if length < 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("length must not be negative: {}", length)))
+        }
+        self.coords.truncate(length as usize);
+        self.labels.truncate(length as usize);
+        Ok(())
+    }
+
+    /// crop_to_sequence()
+    /// 
+    /// Removes leading and trailing gaps so that the linear space spans
+    /// exactly from the first to the last sequence position, inclusive.
+    /// Interior gaps are kept. A space without sequence becomes empty.
+    fn crop_to_sequence(&mut self) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        let first = self.coords.iter().position(|x| *x != -1);
+        let last = self.coords.iter().rposition(|x| *x != -1);
+        let (start, stop) = match (first, last) {
+            (Some(first), Some(last)) => (first, last + 1),
+            _ => (0, 0),
+        };
+        let cropped = self.slice(start, stop);
+        self.coords = cropped.coords;
+        self.labels = cropped.labels;
+        Ok(())
+    }
+
+    /// set(index, value)
+    /// 
+    /// Sets the coordinate at the given relative position to value.
+    /// Negative indices count from the end. A value of -1 makes the
+    /// position a gap. If the position changes between sequence and
+    /// gap, its original label is dropped.
+    fn set(&mut self, index: i32, value: i32) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        let i = self.resolve_index(index)?;
+        if value < -1 {
+            return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", value)))
+        }
+        if self.labels.len() > 0 && (self.coords[i] == -1) != (value == -1) {
+            self.labels[i] = None;
+        }
+        self.coords[i] = value;
+        Ok(())
+    }
+
+    /// pop()
+    /// 
+    /// Removes and returns the last coordinate of the linear space.
+    /// Gaps are returned as -1.
+    fn pop(&mut self) -> PyResult<i32> {
+        self.check_mutable()?;
+        self.clear_cache();
+        match self.coords.pop() {
+            Some(x) => {
+                self.labels.truncate(self.coords.len());
+                Ok(x)
+            },
+            None => Err(exceptions::IndexError::py_err("pop from empty linear space"))
+        }
+    }
+
+    /// push(value)
+    /// 
+    /// Appends a coordinate to the end of the linear space.
+    /// A value of -1 appends a gap.
+    fn push(&mut self, value: i32) -> PyResult<()> {
+        self.check_mutable()?;
+        self.clear_cache();
+        if value < -1 {
+            return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", value)))
+        }
+        self.coords.push(value);
+        if self.labels.len() > 0 {
+            self.labels.push(None);
+        }
+        Ok(())
+    }
+
     // /// Inserts into the linear space at the given position.
     // fn insert(&mut self, pos: i32, start: i32, length: i32) -> PyResult<()> {
     //     // Insert to start of list if pos is 0,
@@ -809,6 +2691,57 @@ impl CoordSpace {
         }
     }
 
+    /// sequence_bounds()
+    /// 
+    /// Returns the smallest and largest sequence coordinates as a
+    /// half-open (start, stop) range. Gaps are ignored.
+    fn sequence_bounds(&self) -> PyResult<(i32, i32)> {
+        let seq_coords = self.coords.iter().filter(|x| **x >= 0);
+        match (seq_coords.clone().min(), seq_coords.max()) {
+            (Some(min), Some(max)) => Ok((*min, max + 1)),
+            _ => Err(exceptions::ValueError::py_err("linear space has no sequence coordinates")),
+        }
+    }
+
+    /// assert_monotonic()
+    /// 
+    /// Raises ValueError at the first relative index where a sequence
+    /// coordinate is not greater than the previous sequence coordinate.
+    /// Gaps are skipped.
+    fn assert_monotonic(&self) -> PyResult<()> {
+        let mut last: Option<i32> = None;
+        for (i, x) in self.coords.iter().enumerate().filter(|(_, x)| **x != -1) {
+            if let Some(prev) = last {
+                if *x <= prev {
+                    return Err(exceptions::ValueError::py_err(
+                        format!("coordinate at index {} is not increasing: {} <= {}", i, x, prev)))
+                }
+            }
+            last = Some(*x);
+        }
+        Ok(())
+    }
+
+    /// sequence_intervals()
+    /// 
+    /// Returns the half-open (start, stop) ranges of absolute
+    /// coordinates covered by each run of consecutive sequence
+    /// coordinates. Gaps and jumps in coordinates separate ranges.
+    fn sequence_intervals(&self) -> PyResult<Vec<(i32, i32)>> {
+        let mut intervals: Vec<(i32, i32)> = Vec::new();
+        for i in 0..self.coords.len() {
+            let x = self.coords[i];
+            if x == -1 {
+                continue
+            }
+            match intervals.last_mut() {
+                Some((_, stop)) if i > 0 && self.coords[i-1] == *stop - 1 && x == *stop => *stop += 1,
+                _ => intervals.push((x, x + 1)),
+            }
+        }
+        Ok(intervals)
+    }
+
     /// len_all()
     /// 
     /// Returns the total length of the linear space.
@@ -833,7 +2766,165 @@ impl CoordSpace {
         let length = self.coords.iter().filter(|x| **x < 0).collect::<Vec<&i32>>().len();
         Ok(length as i32)
     }
-    
+
+    /// find_gap(min_length)
+    /// 
+    /// Returns the start index and length of the first gap run that is
+    /// at least min_length long. Returns None if there is no such gap.
+    fn find_gap(&self, min_length: i32) -> PyResult<Option<(i32, i32)>> {
+        if min_length <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("minimum length must be greater than 0: {}", min_length)))
+        }
+        for (is_gap, start, stop) in self.runs() {
+            if is_gap && (stop - start) as i32 >= min_length {
+                return Ok(Some((start as i32, (stop - start) as i32)))
+            }
+        }
+        Ok(None)
+    }
+
+    /// is_gap_at(index)
+    /// 
+    /// Returns True if the position at the given relative index is
+    /// a gap. Negative indices count from the end.
+    fn is_gap_at(&self, index: i32) -> PyResult<bool> {
+        Ok(self.coords[self.resolve_index(index)?] == -1)
+    }
+
+    /// is_sequence_at(index)
+    /// 
+    /// Returns True if the position at the given relative index is
+    /// sequence. Negative indices count from the end.
+    fn is_sequence_at(&self, index: i32) -> PyResult<bool> {
+        Ok(!self.is_gap_at(index)?)
+    }
+
+    /// state_at(index)
+    /// 
+    /// Returns the PositionState of the position at the given relative
+    /// index. Negative indices count from the end.
+    fn state_at(&self, index: i32) -> PyResult<PositionState> {
+        Ok(PositionState::from_coord(self.coords[self.resolve_index(index)?]))
+    }
+
+    /// to_states()
+    /// 
+    /// Returns the PositionState of every position in the linear space.
+    fn to_states(&self) -> PyResult<Vec<PositionState>> {
+        Ok(self.coords.iter().map(|x| PositionState::from_coord(*x)).collect())
+    }
+
+    /// gap_entropy()
+    /// 
+    /// Returns the binary Shannon entropy, in bits, of the proportions
+    /// of gap and sequence positions. Returns 0.0 if the space is empty
+    /// or contains only gaps or only sequence.
+    fn gap_entropy(&self) -> PyResult<f64> {
+        if self.coords.len() == 0 {
+            return Ok(0.0)
+        }
+        let gaps = self.coords.iter().filter(|x| **x == -1).count();
+        let p = gaps as f64 / self.coords.len() as f64;
+        if p == 0.0 || p == 1.0 {
+            return Ok(0.0)
+        }
+        Ok(-p * p.log2() - (1.0 - p) * (1.0 - p).log2())
+    }
+
+    /// run_summary()
+    /// 
+    /// Returns the number of sequence runs and the number of gap runs
+    /// as a (sequence, gap) tuple.
+    fn run_summary(&self) -> PyResult<(i32, i32)> {
+        let mut seq_runs = 0;
+        let mut gap_runs = 0;
+        for i in 0..self.coords.len() {
+            let is_gap = self.coords[i] == -1;
+            if i == 0 || is_gap != (self.coords[i-1] == -1) {
+                if is_gap {
+                    gap_runs += 1;
+                } else {
+                    seq_runs += 1;
+                }
+            }
+        }
+        Ok((seq_runs, gap_runs))
+    }
+
+    /// max_gap_length()
+    /// 
+    /// Returns the length of the longest run of gaps, or 0 if the
+    /// space has no gaps.
+    fn max_gap_length(&self) -> PyResult<i32> {
+        Ok(self.longest_run(true))
+    }
+
+    /// longest_sequence_run()
+    /// 
+    /// Returns the length of the longest run of sequence positions,
+    /// or 0 if the space has no sequence.
+    fn longest_sequence_run(&self) -> PyResult<i32> {
+        Ok(self.longest_run(false))
+    }
+
+    /// where_coord(predicate)
+    /// 
+    /// Returns the relative positions where predicate(coordinate)
+    /// is true. Gaps are passed to the predicate as -1.
+    fn where_coord(&self, predicate: PyObject) -> PyResult<Vec<i32>> {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let mut positions: Vec<i32> = Vec::new();
+        for (i, coord) in self.coords.iter().enumerate() {
+            if predicate.call1(py, (*coord,))?.is_true(py)? {
+                positions.push(i as i32);
+            }
+        }
+        Ok(positions)
+    }
+
+    /// map_coords(callback)
+    /// 
+    /// Returns a new CoordSpace where every sequence coordinate is
+    /// replaced by callback(coordinate). Gaps are kept unchanged.
+    /// The callback must return a non-negative integer.
+    fn map_coords(&self, callback: PyObject) -> PyResult<CoordSpace> {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let mut coords: Vec<i32> = Vec::with_capacity(self.coords.len());
+        for coord in self.coords.iter() {
+            if *coord == -1 {
+                coords.push(-1);
+                continue
+            }
+            let value: i32 = callback.call1(py, (*coord,))?.extract(py)?;
+            if value < 0 {
+                return Err(exceptions::ValueError::py_err(
+                    format!("callback returned a negative coordinate: {}", value)))
+            }
+            coords.push(value);
+        }
+        Ok(CoordSpace::with_labels(coords, self.labels.clone()))
+    }
+
+    /// coordinate_histogram(bin_size)
+    /// 
+    /// Returns a dictionary mapping each bin, computed as
+    /// floor(coordinate / bin_size), to the number of coordinates
+    /// in that bin. Gaps are excluded.
+    fn coordinate_histogram(&self, bin_size: i32) -> PyResult<HashMap<i32, i32>> {
+        if bin_size <= 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("bin size must be greater than 0: {}", bin_size)))
+        }
+        let mut histogram: HashMap<i32, i32> = HashMap::new();
+        for coord in self.coords.iter().filter(|x| **x >= 0) {
+            *histogram.entry(coord / bin_size).or_insert(0) += 1;
+        }
+        Ok(histogram)
+    }
+
     // Format conversion
 
     #[staticmethod]
@@ -842,8 +2933,7 @@ impl CoordSpace {
     /// Returns a linear space created using the given list of blocks.
     fn from_blocks(blocks: Vec<&Block>) -> PyResult<CoordSpace> {
         if blocks.len() == 0 {
-            let coords: Vec<i32> = Vec::new();
-            return Ok(CoordSpace{ coords })
+            return Ok(CoordSpace::new(Vec::new()))
         }
         match blocks_to_arrays(blocks) {
             Ok((data, ids)) => {
@@ -859,7 +2949,7 @@ impl CoordSpace {
                         return Err(exceptions::ValueError::py_err(format!("unsupported ID: {}. Use \"s\" for sequence or \"g\" for gap.", id)))
                     }
                 }
-                Ok(CoordSpace { coords: new_data })
+                Ok(CoordSpace::new(new_data))
             },
             Err(x) => return Err(x)
         }
@@ -872,35 +2962,67 @@ impl CoordSpace {
     /// Returns a linear space created using the corresponding lists of
     /// coordinates and ids.
     fn from_arrays(data: Vec<i32>, ids: Vec<String>) -> PyResult<CoordSpace> {
+        match CoordSpace::coords_from_arrays(&data, &ids) {
+            Ok(coords) => Ok(CoordSpace::new(coords)),
+            Err(msg) => Err(exceptions::ValueError::py_err(msg)),
+        }
+    }
+
+    #[staticmethod]
+    /// from_arrays_labeled(coordinates, ids)
+    /// 
+    /// Returns a linear space created using the corresponding lists of
+    /// coordinates and ids, keeping the original ids as labels.
+    /// Positions with the id "g" or a coordinate of -1 are gaps,
+    /// all other positions are sequence.
+    fn from_arrays_labeled(data: Vec<i32>, ids: Vec<String>) -> PyResult<CoordSpace> {
         if data.len() != ids.len() {
             return Err(exceptions::ValueError::py_err("lengths of data and ids do not match"))
         }
-        if data.len() == 0 {
-            let coords: Vec<i32> = Vec::new();
-            return Ok(CoordSpace{ coords })
-        }
-        let mut coords: Vec<i32> = Vec::new();
+        let mut coords: Vec<i32> = Vec::with_capacity(data.len());
+        let mut labels: Vec<Option<String>> = Vec::with_capacity(data.len());
         for i in 0..data.len() {
             let x = data[i];
             let id = &ids[i];
-            if id == "s" {
-                coords.push(x);
-            } else if id == "g" {
+            if id == "g" || x == -1 {
                 coords.push(-1);
+            } else if x >= 0 {
+                coords.push(x);
             } else {
-                return Err(exceptions::ValueError::py_err(format!("unsupported ID: {}. Use \"s\" for sequence or \"g\" for gap.", id)))
+                return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", x)))
             }
+            labels.push(Some(id.to_string()));
+        }
+        Ok(CoordSpace::with_labels(coords, labels))
+    }
+
+    /// labels()
+    /// 
+    /// Returns the original id of each position, or None for
+    /// positions that were not created with a label.
+    fn labels(&self) -> PyResult<Vec<Option<String>>> {
+        if self.labels.len() == 0 {
+            return Ok(vec![None; self.coords.len()])
         }
-        Ok(CoordSpace{ coords })
+        Ok(self.labels.clone())
     }
 
     /// to_blocks()
     /// 
     /// Returns the linear space as a list of blocks.
+    /// A repeated coordinate is merged into the current block,
+    /// whether or not the space is labeled.
     fn to_blocks(&self) -> PyResult<Vec<Block>> {
+        if let Some(blocks) = &self.block_cache {
+            return Ok(blocks.clone())
+        }
         if self.coords.len() == 0 {
             return Ok(Vec::new())
         }
+        if self.labels.len() > 0 {
+            let (data, ids) = self.to_labeled_arrays()?;
+            return arrays_to_blocks(data, ids, None)
+        }
         // Declare variables
         let mut blocks: Vec<Block> = Vec::new();
         let mut last_start: i32 = self.coords[0];
@@ -909,10 +3031,11 @@ impl CoordSpace {
             x if x == -1 => "g".to_string(),
             x => return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", x))),
         };
-        let mut negative_length: i32 = 0;
+        // Length of the current gap run
+        let mut negative_length: i32 = if self.coords[0] == -1 { 1 } else { 0 };
 
         for i in 1..self.coords.len() {
-            let c_id: String = match self.coords[0] {
+            let c_id: String = match self.coords[i] {
                 x if x >= 0 => "s".to_string(),
                 x if x == -1 => "g".to_string(),
                 x => return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", x))),
@@ -928,10 +3051,10 @@ impl CoordSpace {
             } else if c_pos == -1 && p_pos >= 0 {
                 // Create new block and push
                 blocks.push(Block{ id: last_id, start: last_start, stop: p_pos + 1});
-                // Assign current id as last_id and current pos as last_start
+                // Assign current id as last_id and start a new gap run
                 last_id = c_id;
-                last_start = c_pos;
-                negative_length = 0;
+                last_start = 0;
+                negative_length = 1;
             } else if c_pos >= 0 && p_pos == -1 {
                 // Create new block and push
                 blocks.push(Block{ id: last_id, start: 0, stop: negative_length});
@@ -940,6 +3063,10 @@ impl CoordSpace {
                 last_start = c_pos;
                 negative_length = 0;
             } else if c_pos >= 0 && p_pos >= 0 {
+                if c_pos == p_pos {
+                    // Merge repeated coordinate into the current block
+                    continue
+                }
                 if c_pos != p_pos + 1 {
                     // Create new block and push
                     blocks.push(Block{ id: last_id, start: last_start, stop: p_pos + 1});
@@ -949,10 +3076,221 @@ impl CoordSpace {
                 }
             }
         }
-        blocks.push(Block{ id: last_id, start: last_start, stop: self.coords.last().unwrap() + 1});
+        match self.coords.last() {
+            Some(-1) => blocks.push(Block{ id: last_id, start: 0, stop: negative_length}),
+            Some(x) => blocks.push(Block{ id: last_id, start: last_start, stop: x + 1}),
+            None => (),
+        }
         Ok(blocks)
     }
 
+    /// build_coverage_index()
+    /// 
+    /// Computes and caches the number of sequence positions before
+    /// each relative position, so that range_sequence_count runs in
+    /// constant time. The index is cleared when the space is modified.
+    fn build_coverage_index(&mut self) -> PyResult<()> {
+        let mut index: Vec<i32> = Vec::with_capacity(self.coords.len() + 1);
+        let mut count = 0;
+        index.push(count);
+        for x in self.coords.iter() {
+            if *x != -1 {
+                count += 1;
+            }
+            index.push(count);
+        }
+        self.coverage_index = Some(index);
+        Ok(())
+    }
+
+    /// range_sequence_count(start, stop)
+    /// 
+    /// Returns the number of sequence positions in the half-open
+    /// relative range from start to stop.
+    fn range_sequence_count(&self, start: i32, stop: i32) -> PyResult<i32> {
+        if start > stop {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must be less than stop: {} !< {}", start, stop)))
+        }
+        if start < 0 || stop > self.coords.len() as i32 {
+            return Err(exceptions::IndexError::py_err(
+                format!("range out of range: {}:{}", start, stop)))
+        }
+        match &self.coverage_index {
+            Some(index) => Ok(index[stop as usize] - index[start as usize]),
+            None => Ok(self.coords[start as usize..stop as usize].iter()
+                .filter(|x| **x != -1)
+                .count() as i32),
+        }
+    }
+
+    /// count_blocks()
+    /// 
+    /// Returns the number of blocks returned by to_blocks.
+    fn count_blocks(&self) -> PyResult<i32> {
+        if let Some(blocks) = &self.block_cache {
+            return Ok(blocks.len() as i32)
+        }
+        Ok(self.to_blocks()?.len() as i32)
+    }
+
+    /// coalesce()
+    /// 
+    /// Computes and caches the blocks of the linear space so that
+    /// later calls to to_blocks and count_blocks do not rescan the
+    /// coordinates. The cache is cleared when the space is modified.
+    fn coalesce(&mut self) -> PyResult<()> {
+        self.block_cache = None;
+        self.block_cache = Some(self.to_blocks()?);
+        Ok(())
+    }
+
+    /// enumerate_coords()
+    /// 
+    /// Returns the linear space as a list of (relative position, coordinate)
+    /// pairs, including gaps.
+    fn enumerate_coords(&self) -> PyResult<Vec<(i32, i32)>> {
+        Ok(self.coords.iter().enumerate().map(|(i, x)| (i as i32, *x)).collect())
+    }
+
+    /// gaps_as(placeholder)
+    /// 
+    /// Returns the coordinates of the linear space as a list where
+    /// every gap is replaced by the given placeholder value.
+    fn gaps_as(&self, placeholder: i32) -> PyResult<Vec<i32>> {
+        Ok(self.coords.iter().map(|x| if *x == -1 { placeholder } else { *x }).collect())
+    }
+
+    /// to_rle()
+    /// 
+    /// Returns the linear space as a list of (anchor, length, is_gap)
+    /// runs. For sequence runs, the anchor is the first coordinate of
+    /// a run of consecutive coordinates. For gap runs, the anchor is -1.
+    fn to_rle(&self) -> PyResult<Vec<(i32, i32, i32)>> {
+        let mut runs: Vec<(i32, i32, i32)> = Vec::new();
+        for (i, coord) in self.coords.iter().enumerate() {
+            let is_gap = if *coord == -1 { 1 } else { 0 };
+            if let Some((anchor, length, last_is_gap)) = runs.last_mut() {
+                if *last_is_gap == is_gap &&
+                        (is_gap == 1 || *coord == *anchor + *length) {
+                    *length += 1;
+                    continue
+                }
+            }
+            if *coord < -1 {
+                return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", self.coords[i])))
+            }
+            runs.push((*coord, 1, is_gap));
+        }
+        Ok(runs)
+    }
+
+    #[staticmethod]
+    /// from_rle(runs)
+    /// 
+    /// Returns a linear space created from a list of
+    /// (anchor, length, is_gap) runs produced by to_rle.
+    fn from_rle(runs: Vec<(i32, i32, i32)>) -> PyResult<CoordSpace> {
+        let mut coords: Vec<i32> = Vec::new();
+        for (anchor, length, is_gap) in runs {
+            if length < 0 {
+                return Err(exceptions::ValueError::py_err(format!("run length must not be negative: {}", length)))
+            }
+            match is_gap {
+                1 => coords.extend(vec![-1; length as usize]),
+                0 if anchor >= 0 => coords.extend(anchor..anchor + length),
+                0 => return Err(exceptions::ValueError::py_err(format!("unexpected sequence anchor: {}", anchor))),
+                x => return Err(exceptions::ValueError::py_err(format!("unexpected gap flag: {}", x))),
+            }
+        }
+        Ok(CoordSpace::new(coords))
+    }
+
+    #[staticmethod]
+    /// from_mask_and_coords(is_gap, seq_coords)
+    /// 
+    /// Returns a linear space created from a gap mask, using the next
+    /// coordinate from seq_coords for each position that is not a gap.
+    fn from_mask_and_coords(is_gap: Vec<bool>, seq_coords: Vec<i32>) -> PyResult<CoordSpace> {
+        let seq_count = is_gap.iter().filter(|x| !**x).count();
+        if seq_count != seq_coords.len() {
+            return Err(exceptions::ValueError::py_err(
+                format!("number of sequence positions does not match number of coordinates: {} != {}",
+                        seq_count, seq_coords.len())))
+        }
+        let mut seq_coords = seq_coords.into_iter();
+        let mut coords: Vec<i32> = Vec::with_capacity(is_gap.len());
+        for gap in is_gap {
+            if gap {
+                coords.push(-1);
+                continue
+            }
+            match seq_coords.next() {
+                Some(x) if x >= 0 => coords.push(x),
+                Some(x) => return Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", x))),
+                None => unreachable!(),
+            }
+        }
+        Ok(CoordSpace::new(coords))
+    }
+
+    #[staticmethod]
+    /// from_bitmap(bitmap, start)
+    /// 
+    /// Returns a linear space where each True item is assigned an
+    /// incrementing coordinate beginning at start, and each False item
+    /// is a gap.
+    fn from_bitmap(bitmap: Vec<bool>, start: i32) -> PyResult<CoordSpace> {
+        if start < 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must not be negative: {}", start)))
+        }
+        let mut pos = start;
+        let coords = bitmap.iter().map(|covered| {
+            if *covered {
+                pos += 1;
+                pos - 1
+            } else {
+                -1
+            }
+        }).collect();
+        Ok(CoordSpace::new(coords))
+    }
+
+    #[staticmethod]
+    #[args(seq_char = "\"x\"", gap_char = "\"-\"")]
+    /// from_mask_str(mask, start, seq_char='x', gap_char='-')
+    /// 
+    /// Returns a linear space created from a mask string produced by
+    /// to_mask_str. Sequence positions are assigned incrementing
+    /// coordinates beginning at start. Raises ValueError if seq_char
+    /// or gap_char is not a single character.
+    fn from_mask_str(mask: &str, start: i32, seq_char: &str, gap_char: &str) -> PyResult<CoordSpace> {
+        let seq_char = CoordSpace::mask_char("seq_char", seq_char)?;
+        let gap_char = CoordSpace::mask_char("gap_char", gap_char)?;
+        if start < 0 {
+            return Err(exceptions::ValueError::py_err(
+                format!("start must not be negative: {}", start)))
+        }
+        if seq_char == gap_char {
+            return Err(exceptions::ValueError::py_err(
+                format!("seq_char and gap_char must be different: {}", seq_char)))
+        }
+        let mut coords: Vec<i32> = Vec::with_capacity(mask.len());
+        let mut pos = start;
+        for c in mask.chars() {
+            if c == seq_char {
+                coords.push(pos);
+                pos += 1;
+            } else if c == gap_char {
+                coords.push(-1);
+            } else {
+                return Err(exceptions::ValueError::py_err(format!("unexpected mask character: {}", c)))
+            }
+        }
+        Ok(CoordSpace::new(coords))
+    }
+
     /// to_arrays()
     /// 
     /// Returns the linear space as a list of integer coordinates.
@@ -1011,12 +3349,67 @@ impl CoordSpace {
         Ok(strings.join(","))
     }
 
+    #[args(include_gaps = "true")]
+    /// to_bedgraph(chrom, include_gaps=True)
+    /// 
+    /// Returns a bedGraph coverage track with one tab-separated
+    /// chrom, start, stop, value line per run. Start and stop are
+    /// 0-based half-open relative positions. The value is 1 for
+    /// sequence runs and 0 for gap runs, which are omitted if
+    /// include_gaps is False.
+    fn to_bedgraph(&self, chrom: &str, include_gaps: bool) -> PyResult<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for (is_gap, start, stop) in self.runs() {
+            if is_gap && !include_gaps {
+                continue
+            }
+            lines.push(format!("{}\t{}\t{}\t{}", chrom, start, stop, if is_gap { 0 } else { 1 }));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    #[args(seq_char = "\"x\"", gap_char = "\"-\"")]
+    /// to_mask_str(seq_char='x', gap_char='-')
+    /// 
+    /// Returns a string with one character per position, using
+    /// seq_char for sequence and gap_char for gaps.
+    /// Raises ValueError if seq_char or gap_char is not a single character.
+    fn to_mask_str(&self, seq_char: &str, gap_char: &str) -> PyResult<String> {
+        let seq_char = CoordSpace::mask_char("seq_char", seq_char)?;
+        let gap_char = CoordSpace::mask_char("gap_char", gap_char)?;
+        Ok(self.coords.iter().map(|x| if *x == -1 { gap_char } else { seq_char }).collect())
+    }
+
     /// copy()
     /// 
     /// Returns a deep copy of the current linear space.
     fn copy(&self) -> PyResult<CoordSpace> {
-        let coords = self.coords.clone();
-        Ok(CoordSpace{ coords })
+        Ok(self.clone())
+    }
+
+    /// freeze()
+    /// 
+    /// Marks the linear space as immutable so that it can be hashed,
+    /// for example to be used as a dict key. Methods that modify a
+    /// frozen space raise RuntimeError.
+    fn freeze(&mut self) -> PyResult<()> {
+        self.frozen = true;
+        Ok(())
+    }
+
+    /// thaw()
+    /// 
+    /// Marks a frozen linear space as mutable again.
+    fn thaw(&mut self) -> PyResult<()> {
+        self.frozen = false;
+        Ok(())
+    }
+
+    /// is_frozen()
+    /// 
+    /// Returns True if the linear space is frozen.
+    fn is_frozen(&self) -> PyResult<bool> {
+        Ok(self.frozen)
     }
 
 }
@@ -1058,8 +3451,272 @@ impl PyObjectProtocol for CoordSpace {
         }
         Ok(strings.join(","))
     }
+
+    fn __richcmp__(&self, other: &CoordSpace, op: CompareOp) -> PyResult<bool> {
+        match op {
+            CompareOp::Eq => Ok(self.coords == other.coords),
+            CompareOp::Ne => Ok(self.coords != other.coords),
+            _ => Err(exceptions::TypeError::py_err("linear spaces can only be compared for equality")),
+        }
+    }
+
+    /// Hashes the coordinates of a frozen linear space. Equal spaces
+    /// hash equally. Raises TypeError if the space is not frozen, since
+    /// mutating a space would invalidate any hash-based storage.
+    fn __hash__(&self) -> PyResult<isize> {
+        if !self.frozen {
+            return Err(exceptions::TypeError::py_err("unhashable CoordSpace: call freeze() first"))
+        }
+        let mut hasher = DefaultHasher::new();
+        self.coords.hash(&mut hasher);
+        Ok(hasher.finish() as isize)
+    }
+}
+
+impl CoordSpace {
+    /// Creates a new unlabeled CoordSpace from a list of coordinates.
+    fn new(coords: Vec<i32>) -> CoordSpace {
+        CoordSpace::with_labels(coords, Vec::new())
+    }
+
+    /// Creates a new CoordSpace from lists of coordinates and labels.
+    fn with_labels(coords: Vec<i32>, labels: Vec<Option<String>>) -> CoordSpace {
+        CoordSpace { coords, labels, block_cache: None, coverage_index: None, frozen: false }
+    }
+
+    /// Returns RuntimeError if the space is frozen.
+    /// Must be called by every method that modifies the space.
+    fn check_mutable(&self) -> PyResult<()> {
+        if self.frozen {
+            return Err(exceptions::RuntimeError::py_err("space is frozen"))
+        }
+        Ok(())
+    }
+
+    /// Returns the character of a one-character mask argument,
+    /// or ValueError if value is not exactly one character long.
+    fn mask_char(name: &str, value: &str) -> PyResult<char> {
+        if value.chars().count() != 1 {
+            return Err(exceptions::ValueError::py_err(
+                format!("{} must be a single character: {:?}", name, value)))
+        }
+        Ok(value.chars().next().unwrap())
+    }
+
+    /// Converts corresponding lists of coordinates and "s" or "g" ids
+    /// into coords, or returns an error message if they are invalid.
+    fn coords_from_arrays(data: &[i32], ids: &[String]) -> Result<Vec<i32>, String> {
+        if data.len() != ids.len() {
+            return Err("lengths of data and ids do not match".to_string())
+        }
+        let mut coords: Vec<i32> = Vec::with_capacity(data.len());
+        for i in 0..data.len() {
+            let x = data[i];
+            let id = &ids[i];
+            if id == "s" {
+                coords.push(x);
+            } else if id == "g" {
+                coords.push(-1);
+            } else {
+                return Err(format!("unsupported ID: {}. Use \"s\" for sequence or \"g\" for gap.", id))
+            }
+        }
+        Ok(coords)
+    }
+
+    /// Clears values computed from coords and labels.
+    /// Must be called by every method that modifies the space.
+    fn clear_cache(&mut self) {
+        self.block_cache = None;
+        self.coverage_index = None;
+    }
+
+    /// Converts a relative position, which may be negative to count
+    /// from the end, into an index into coords.
+    fn resolve_index(&self, index: i32) -> PyResult<usize> {
+        let length = self.coords.len() as i32;
+        let i = if index < 0 { index + length } else { index };
+        if i < 0 || i >= length {
+            return Err(exceptions::IndexError::py_err(format!("index out of range: {}", index)))
+        }
+        Ok(i as usize)
+    }
+
+    /// Returns the id of the position at the given index.
+    /// Uses the stored label if present, otherwise "s" or "g".
+    fn id_at(&self, i: usize) -> PyResult<String> {
+        if let Some(Some(label)) = self.labels.get(i) {
+            return Ok(label.to_string())
+        }
+        match self.coords[i] {
+            x if x >= 0 => Ok("s".to_string()),
+            -1 => Ok("g".to_string()),
+            x => Err(exceptions::ValueError::py_err(format!("unexpected coordinate value: {}", x))),
+        }
+    }
+
+    /// Returns runs of consecutive gap or sequence positions as
+    /// (is_gap, start, stop) tuples of relative indices.
+    fn runs(&self) -> Vec<(bool, usize, usize)> {
+        let mut runs: Vec<(bool, usize, usize)> = Vec::new();
+        let mut last_start: usize = 0;
+        for i in 1..=self.coords.len() {
+            if i == self.coords.len() ||
+                    (self.coords[i] == -1) != (self.coords[i-1] == -1) {
+                runs.push((self.coords[i-1] == -1, last_start, i));
+                last_start = i;
+            }
+        }
+        runs
+    }
+
+    /// Returns the length of the longest gap run if is_gap is true,
+    /// or the longest sequence run otherwise.
+    fn longest_run(&self, is_gap: bool) -> i32 {
+        self.runs().iter()
+            .filter(|(gap, _, _)| *gap == is_gap)
+            .map(|(_, start, stop)| (stop - start) as i32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the space as coordinate and id lists suitable for
+    /// arrays_to_blocks. Gap positions are numbered from 0 within
+    /// each gap run so that gap blocks span 0 to the run length.
+    fn to_labeled_arrays(&self) -> PyResult<(Vec<i32>, Vec<String>)> {
+        let mut data: Vec<i32> = Vec::with_capacity(self.coords.len());
+        let mut ids: Vec<String> = Vec::with_capacity(self.coords.len());
+        let mut gap_offset: i32 = 0;
+        for i in 0..self.coords.len() {
+            let id = self.id_at(i)?;
+            if self.coords[i] == -1 {
+                if i > 0 && (self.coords[i-1] != -1 || ids[i-1] != id) {
+                    gap_offset = 0;
+                }
+                data.push(gap_offset);
+                gap_offset += 1;
+            } else {
+                data.push(self.coords[i]);
+            }
+            ids.push(id);
+        }
+        Ok((data, ids))
+    }
+
+    /// Returns the positions from start to stop as a new CoordSpace.
+    fn slice(&self, start: usize, stop: usize) -> CoordSpace {
+        let labels = if self.labels.len() > 0 {
+            self.labels[start..stop].to_vec()
+        } else {
+            Vec::new()
+        };
+        CoordSpace::with_labels(self.coords[start..stop].to_vec(), labels)
+    }
+}
+
+#[pyclass]
+/// Iterator over fixed-length windows of a CoordSpace.
+pub struct CoordWindowIterator {
+
+    space: CoordSpace,
+    window: usize,
+    step: usize,
+    drop_last: bool,
+    pos: usize,
+    done: bool,
+
+    token: PyToken,
+
+}
+
+impl CoordWindowIterator {
+    /// Returns the next window, or None if there are no more windows.
+    fn next_window(&mut self) -> Option<CoordSpace> {
+        let length = self.space.coords.len();
+        if self.done || self.pos >= length {
+            return None
+        }
+        let mut stop = self.pos + self.window;
+        if stop >= length {
+            self.done = true;
+            if stop > length {
+                if self.drop_last {
+                    return None
+                }
+                stop = length;
+            }
+        }
+        let window = self.space.slice(self.pos, stop);
+        self.pos += self.step;
+        Some(window)
+    }
+}
+
+#[pyproto]
+impl PyIterProtocol for CoordWindowIterator {
+    fn __iter__(&mut self) -> PyResult<PyObject> {
+        Ok(self.into())
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<CoordSpace>> {
+        Ok(self.next_window())
+    }
+}
+
+#[pyclass]
+#[derive(Clone, Copy, PartialEq)]
+/// PositionState
+/// --
+/// 
+/// PositionState represents whether a position in a CoordSpace is
+/// sequence or a gap. The module constants SEQUENCE and GAP are its
+/// only values.
+pub struct PositionState {
+
+    #[prop(get)]
+    pub is_gap: bool,
+
 }
 
+impl PositionState {
+    pub const SEQUENCE: PositionState = PositionState{ is_gap: false };
+    pub const GAP: PositionState = PositionState{ is_gap: true };
+
+    /// Returns the state of a CoordSpace coordinate, where -1 is a gap.
+    fn from_coord(coord: i32) -> PositionState {
+        if coord == -1 { PositionState::GAP } else { PositionState::SEQUENCE }
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for PositionState {
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(if self.is_gap { "GAP" } else { "SEQUENCE" }.to_string())
+    }
+
+    fn __richcmp__(&self, other: &PositionState, op: CompareOp) -> PyResult<bool> {
+        match op {
+            CompareOp::Eq => Ok(self == other),
+            CompareOp::Ne => Ok(self != other),
+            _ => Err(exceptions::TypeError::py_err("position states can only be compared for equality")),
+        }
+    }
+
+    fn __hash__(&self) -> PyResult<isize> {
+        Ok(self.is_gap as isize)
+    }
+}
+
+
+/// Advances the state and returns the next value of a splitmix64
+/// pseudorandom number generator.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
 
 #[pyfunction]
 /// blocks_to_arrays(block_list)
@@ -1079,11 +3736,14 @@ pub fn blocks_to_arrays(blocks: Vec<&Block>) -> PyResult<(Vec<i32>, Vec<String>)
 }
 
 #[pyfunction]
-/// arrays_to_blocks(data, ids)
+/// arrays_to_blocks(data, ids, strict=False)
 /// 
 /// Converts an explicit list of positions into a list of blocks.
-/// Returns a list of Block objects.
-pub fn arrays_to_blocks(data: Vec<i32>, ids: Vec<String>) -> PyResult<Vec<Block>> {
+/// Returns a list of Block objects. A coordinate repeated within the
+/// same id is merged into the current block, or raises ValueError
+/// if strict is True.
+pub fn arrays_to_blocks(data: Vec<i32>, ids: Vec<String>, strict: Option<bool>) -> PyResult<Vec<Block>> {
+    let strict = strict.unwrap_or(false);
     if data.len() != ids.len() {
         return Err(exceptions::ValueError::py_err("lengths of data and ids do not match"))
     }
@@ -1108,6 +3768,14 @@ pub fn arrays_to_blocks(data: Vec<i32>, ids: Vec<String>) -> PyResult<Vec<Block>
         // 2a and 2b are the same scenario, because change in ID should always
         // generate a new block
         if c_id == last_id {
+            if c_pos == p_pos {
+                if strict {
+                    return Err(exceptions::ValueError::py_err(
+                        format!("duplicate coordinate: {}", c_pos)))
+                }
+                // Merge repeated coordinate into the current block
+                continue
+            }
             if c_pos != p_pos + 1 {
                 // Create new block and push
                 blocks.push(Block{ id: last_id.to_string(), start: last_start, stop: p_pos + 1});
@@ -1127,17 +3795,136 @@ pub fn arrays_to_blocks(data: Vec<i32>, ids: Vec<String>) -> PyResult<Vec<Block>
     Ok(blocks)
 }
 
+#[pyfunction]
+/// dedup_spaces(spaces)
+/// 
+/// Removes duplicate CoordSpaces from a list by comparing coordinates.
+/// Returns the list of unique spaces and a list mapping each input
+/// space to the index of its unique representative.
+pub fn dedup_spaces(spaces: Vec<&CoordSpace>) -> PyResult<(Vec<CoordSpace>, Vec<i32>)> {
+    let mut unique: Vec<CoordSpace> = Vec::new();
+    let mut index: Vec<i32> = Vec::with_capacity(spaces.len());
+    let mut seen: HashMap<&Vec<i32>, i32> = HashMap::new();
+    for space in spaces.iter() {
+        let i = *seen.entry(&space.coords).or_insert_with(|| {
+            unique.push((*space).clone());
+            unique.len() as i32 - 1
+        });
+        index.push(i);
+    }
+    Ok((unique, index))
+}
+
+#[pyfunction]
+/// from_arrays_batch(rows)
+/// 
+/// Returns a list of CoordSpaces, one for each (coordinates, ids) row.
+/// Raises ValueError naming the first invalid row.
+pub fn from_arrays_batch(rows: Vec<(Vec<i32>, Vec<String>)>) -> PyResult<Vec<CoordSpace>> {
+    let mut spaces: Vec<CoordSpace> = Vec::with_capacity(rows.len());
+    for (i, (data, ids)) in rows.iter().enumerate() {
+        match CoordSpace::coords_from_arrays(data, ids) {
+            Ok(coords) => spaces.push(CoordSpace::new(coords)),
+            Err(msg) => return Err(exceptions::ValueError::py_err(
+                format!("invalid row {}: {}", i, msg))),
+        }
+    }
+    Ok(spaces)
+}
+
+#[pyfunction]
+/// all_same_length(spaces)
+/// 
+/// Returns the length shared by all CoordSpaces in the list,
+/// or None if their lengths differ or the list is empty.
+pub fn all_same_length(spaces: Vec<&CoordSpace>) -> PyResult<Option<i32>> {
+    let length = match spaces.first() {
+        Some(space) => space.coords.len(),
+        None => return Ok(None)
+    };
+    if spaces.iter().all(|space| space.coords.len() == length) {
+        Ok(Some(length as i32))
+    } else {
+        Ok(None)
+    }
+}
+
+#[pyfunction]
+/// gap_consensus(spaces, threshold)
+/// 
+/// Returns a list with one value per column that is True if the
+/// fraction of CoordSpaces with a gap at that column is at least
+/// threshold. All spaces must have the same length.
+pub fn gap_consensus(spaces: Vec<&CoordSpace>, threshold: f64) -> PyResult<Vec<bool>> {
+    let length = match all_same_length(spaces.clone())? {
+        Some(length) => length as usize,
+        None if spaces.len() == 0 => return Ok(Vec::new()),
+        None => return Err(exceptions::ValueError::py_err(
+            "lengths of spaces do not match"))
+    };
+    let mut gap_counts: Vec<usize> = vec![0; length];
+    for space in spaces.iter() {
+        for (i, x) in space.coords.iter().enumerate() {
+            if *x == -1 {
+                gap_counts[i] += 1;
+            }
+        }
+    }
+    Ok(gap_counts.iter()
+        .map(|count| *count as f64 / spaces.len() as f64 >= threshold)
+        .collect())
+}
+
 #[pymodinit]
-fn position(_py: Python, m: &PyModule) -> PyResult<()> {
+fn position(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Block>()?;
     m.add_class::<BlockSpace>()?;
     m.add_class::<CoordSpace>()?;
+    m.add_class::<GapIterator>()?;
+    m.add_class::<CoordWindowIterator>()?;
+    m.add_class::<BlockWindowIterator>()?;
+    m.add_class::<PositionState>()?;
+
+    m.add("SEQUENCE", Py::new(py, |_| PositionState::SEQUENCE)?)?;
+    m.add("GAP", Py::new(py, |_| PositionState::GAP)?)?;
 
     m.add_function(wrap_function!(blocks_to_linspace))?;
     m.add_function(wrap_function!(list_to_linspace))?;
     m.add_function(wrap_function!(arrays_to_linspace))?;
     m.add_function(wrap_function!(block_str_to_linspace))?;
     m.add_function(wrap_function!(simple_block_str_to_linspace))?;
+    m.add_function(wrap_function!(compressed_str_v2_to_linspace))?;
+    m.add_function(wrap_function!(dedup_spaces))?;
+    m.add_function(wrap_function!(all_same_length))?;
+    m.add_function(wrap_function!(arrays_to_blocks))?;
+    m.add_function(wrap_function!(gap_consensus))?;
+    m.add_function(wrap_function!(from_arrays_batch))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(id: &str, start: i32, stop: i32) -> Block {
+        Block{ id: id.to_string(), start, stop }
+    }
+
+    #[test]
+    fn block_sort_unstable_matches_sort() {
+        let blocks = vec![
+            block("s", 5, 9), block("g", 0, 3), block("s", 0, 3),
+            block("a", 5, 7), block("s", -1, 2), block("g", 0, 3),
+        ];
+        let mut stable = blocks.clone();
+        stable.sort();
+        let mut unstable = blocks.clone();
+        unstable.sort_unstable();
+        assert!(stable == unstable);
+        assert!(stable == vec![
+            block("s", -1, 2), block("g", 0, 3), block("g", 0, 3),
+            block("s", 0, 3), block("a", 5, 7), block("s", 5, 9),
+        ]);
+    }
+}